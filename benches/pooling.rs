@@ -0,0 +1,67 @@
+//! Compares spawning and despawning every projectile against recycling them through the
+//! projectile pool, with thousands of projectiles in flight.
+
+use bevy::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rustaga::{
+    enemy::Enemy,
+    projectile::{
+        FireProjectileEvent, Projectile, ProjectileKind, ProjectileOwner, ProjectileResource,
+    },
+    sim::Simulation,
+};
+
+const SHOTS_PER_FRAME: usize = 400;
+
+fn projectile_sim(pooling: bool) -> Simulation {
+    let mut sim = Simulation::new();
+    sim.despawn_all::<With<Enemy>>();
+
+    let mut projectile_resource = sim.world().resource_mut::<ProjectileResource>();
+    projectile_resource.pooling = pooling;
+    // Fast enough to leave the play area within about ten frames, so shots are spent and
+    // replaced at the same rate they're fired
+    projectile_resource
+        .kinds
+        .get_mut(&ProjectileKind::Bullet)
+        .unwrap()
+        .velocity = Vec2::new(0., 3000.);
+
+    sim
+}
+
+fn fire_volley_and_step(sim: &mut Simulation) {
+    for shot in 0..SHOTS_PER_FRAME {
+        let x = shot as f32 / SHOTS_PER_FRAME as f32 * 400. - 200.;
+        sim.world().send_event(FireProjectileEvent {
+            kind: ProjectileKind::Bullet,
+            owner: ProjectileOwner::Player,
+            origin: Transform::from_xyz(x, -200., 0.),
+            modifiers: default(),
+        });
+    }
+    sim.step(1);
+}
+
+fn projectile_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("projectile_churn");
+
+    for (name, pooling) in [("spawn_despawn", false), ("pooled", true)] {
+        let mut sim = projectile_sim(pooling);
+
+        // Fill the play area before measuring
+        for _ in 0..30 {
+            fire_volley_and_step(&mut sim);
+        }
+        assert!(sim.count_active::<With<Projectile>>() > SHOTS_PER_FRAME);
+
+        group.bench_function(BenchmarkId::new("frame", name), |b| {
+            b.iter(|| fire_volley_and_step(&mut sim))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, projectile_churn);
+criterion_main!(benches);
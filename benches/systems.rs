@@ -0,0 +1,151 @@
+//! Frame times of the hot gameplay systems at bullet-hell scale.
+//!
+//! Every benchmark steps a headless [`Simulation`], so they need no window, GPU or audio
+//! device and run the same on CI as locally.
+
+use bevy::prelude::*;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rustaga::{
+    boundary::BoundaryBehavior,
+    enemy::{Enemy, Health},
+    movement::Velocity,
+    play_area::PlayArea,
+    projectile::{FireProjectileEvent, ProjectileKind, ProjectileOwner, ProjectileResource},
+    sim::Simulation,
+};
+
+/// `count` points spread evenly over the rectangle centered on `center` with `size`.
+fn spread(count: usize, center: Vec2, size: Vec2) -> impl Iterator<Item = Vec2> {
+    let columns = (count as f32).sqrt().ceil() as usize;
+    let rows = count.div_ceil(columns);
+
+    (0..count).map(move |i| {
+        let cell = Vec2::new((i % columns) as f32 + 0.5, (i / columns) as f32 + 0.5);
+        center - size / 2. + cell / Vec2::new(columns as f32, rows as f32) * size
+    })
+}
+
+/// A simulation with nothing on stage but the player.
+fn empty_sim() -> Simulation {
+    let mut sim = Simulation::new();
+    sim.despawn_all::<With<Enemy>>();
+    sim
+}
+
+fn spawn_enemies(sim: &mut Simulation, count: usize, velocity: Vec2) {
+    let play_area = Vec2::new(
+        sim.resource::<PlayArea>().width,
+        sim.resource::<PlayArea>().height,
+    );
+
+    for position in spread(count, Vec2::new(0., play_area.y / 4.), play_area / 2.) {
+        sim.spawn((
+            Enemy,
+            // Enough to survive every benchmark, so the workload stays the same throughout
+            Health(f32::MAX),
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.))),
+            Velocity(velocity),
+            BoundaryBehavior::Wrap,
+        ));
+    }
+}
+
+fn fire_bullets(sim: &mut Simulation, count: usize, origins: impl Fn(usize) -> Vec2) {
+    for shot in 0..count {
+        sim.world().send_event(FireProjectileEvent {
+            kind: ProjectileKind::Bullet,
+            owner: ProjectileOwner::Player,
+            origin: Transform::from_translation(origins(shot).extend(0.)),
+            modifiers: default(),
+        });
+    }
+}
+
+fn bullet_spec(sim: &mut Simulation) -> Mut<'_, rustaga::projectile::ProjectileSpec> {
+    sim.world()
+        .resource_mut::<ProjectileResource>()
+        .map_unchanged(|projectile_resource| {
+            projectile_resource
+                .kinds
+                .get_mut(&ProjectileKind::Bullet)
+                .unwrap()
+        })
+}
+
+/// Enemies flying across and wrapping around the play area.
+fn movement(c: &mut Criterion) {
+    let mut group = c.benchmark_group("movement");
+
+    for enemies in [1_000, 5_000] {
+        let mut sim = empty_sim();
+        spawn_enemies(&mut sim, enemies, Vec2::new(-200., 50.));
+
+        group.bench_function(BenchmarkId::from_parameter(enemies), |b| {
+            b.iter(|| sim.step(1))
+        });
+    }
+
+    group.finish();
+}
+
+/// Volleys of bullets leaving the top of the play area as fast as they're fired.
+fn offscreen_despawn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("offscreen_despawn");
+
+    for shots_per_frame in [100, 500] {
+        let mut sim = empty_sim();
+        // Out of the play area within about ten frames
+        bullet_spec(&mut sim).velocity = Vec2::new(0., 3000.);
+
+        let volley = move |sim: &mut Simulation| {
+            fire_bullets(sim, shots_per_frame, |shot| {
+                Vec2::new(shot as f32 / shots_per_frame as f32 * 400. - 200., -200.)
+            });
+            sim.step(1);
+        };
+
+        // Reach a steady number in flight before measuring
+        for _ in 0..30 {
+            volley(&mut sim);
+        }
+
+        group.bench_function(BenchmarkId::from_parameter(shots_per_frame), |b| {
+            b.iter(|| volley(&mut sim))
+        });
+    }
+
+    group.finish();
+}
+
+/// Stationary bullets hanging among stationary enemies, checked against each other every
+/// frame.
+fn collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collisions");
+
+    for (enemies, bullets) in [(250, 1_000), (1_000, 4_000)] {
+        let mut sim = empty_sim();
+        spawn_enemies(&mut sim, enemies, Vec2::ZERO);
+
+        {
+            let mut bullet_spec = bullet_spec(&mut sim);
+            bullet_spec.velocity = Vec2::ZERO;
+            bullet_spec.spawn_offset = Vec2::ZERO;
+            // Keep bullets alive through every hit
+            bullet_spec.modifiers.pierce = 1_000_000;
+        }
+
+        let origins: Vec<Vec2> = spread(bullets, Vec2::ZERO, Vec2::splat(480.)).collect();
+        fire_bullets(&mut sim, bullets, |shot| origins[shot]);
+        sim.step(1);
+
+        group.bench_function(
+            BenchmarkId::from_parameter(format!("{enemies}x{bullets}")),
+            |b| b.iter(|| sim.step(1)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, movement, offscreen_despawn, collisions);
+criterion_main!(benches);
@@ -0,0 +1,310 @@
+use bevy::prelude::*;
+
+use crate::{
+    despawn_all,
+    enemy::EnemyDestroyedEvent,
+    player::Thrust,
+    pool::{EntityPool, Pooled},
+    sprites::{SpriteName, SpriteRegistry},
+    visual_state::VisualStateSystem,
+    GameState,
+};
+
+/// Every frame-by-frame animation in the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Animation {
+    Explosion,
+    BombSpin,
+}
+
+impl Animation {
+    /// Must all be on the same sheet, which the entity's texture atlas must be for
+    pub fn frames(&self) -> &'static [SpriteName] {
+        match self {
+            Animation::Explosion => &[
+                SpriteName::ExplosionSparks,
+                SpriteName::ExplosionSmoke,
+                SpriteName::ExplosionPuff,
+            ],
+            Animation::BombSpin => &[
+                SpriteName::Bomb,
+                SpriteName::BombSpin1,
+                SpriteName::BombSpin2,
+            ],
+        }
+    }
+
+    /// Frames per second
+    pub fn fps(&self) -> f32 {
+        match self {
+            Animation::Explosion => 10.,
+            Animation::BombSpin => 12.,
+        }
+    }
+
+    pub fn looping(&self) -> bool {
+        match self {
+            Animation::Explosion => false,
+            Animation::BombSpin => true,
+        }
+    }
+}
+
+/// Plays an [`Animation`] on the entity's [`TextureAtlasSprite`].
+#[derive(Component, Debug)]
+pub struct SpriteAnimation {
+    pub animation: Animation,
+    /// Time since the animation started, in seconds
+    elapsed: f32,
+    finished: bool,
+}
+
+impl SpriteAnimation {
+    pub fn new(animation: Animation) -> Self {
+        Self {
+            animation,
+            elapsed: 0.,
+            finished: false,
+        }
+    }
+
+    /// The frame currently showing, into [`Animation::frames`].
+    pub fn frame(&self) -> usize {
+        let frame_count = self.animation.frames().len();
+        let frame = (self.elapsed * self.animation.fps()) as usize;
+
+        if self.animation.looping() {
+            frame % frame_count
+        } else {
+            frame.min(frame_count - 1)
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Sent once a non-looping animation has shown its last frame for a full frame.
+pub struct AnimationFinishedEvent {
+    pub entity: Entity,
+    pub animation: Animation,
+}
+
+/// The blast left where an enemy was destroyed, parked for reuse once it has played out.
+#[derive(Component)]
+pub struct Explosion;
+
+#[derive(Bundle)]
+struct ExplosionBundle {
+    explosion: Explosion,
+    #[bundle]
+    sprite: SpriteSheetBundle,
+    sprite_animation: SpriteAnimation,
+    pooled: Pooled,
+}
+
+/// Rocks the entity back and forth around its resting rotation.
+#[derive(Component, Debug)]
+pub struct Wobble {
+    /// The furthest the entity turns either way, in radians
+    pub amplitude: f32,
+    /// Wobbles per second
+    pub frequency: f32,
+    /// Where in the wobble the entity starts, so neighbours don't wobble in lockstep
+    pub phase: f32,
+    applied: f32,
+}
+
+impl Wobble {
+    pub fn new(amplitude: f32, frequency: f32) -> Self {
+        Self {
+            amplitude,
+            frequency,
+            phase: fastrand::f32() * std::f32::consts::TAU,
+            applied: 0.,
+        }
+    }
+}
+
+/// Narrows the ship as it steers sideways, as if rolling into the turn.
+///
+/// The sprite sheets have no banked frames, so the roll is faked by squashing the sprite.
+#[derive(Component, Debug)]
+pub struct Banking {
+    /// From -1 (rolled fully left) to 1 (rolled fully right)
+    pub roll: f32,
+    /// The squash applied to the transform's scale, so it can be taken back off
+    applied: f32,
+}
+
+impl Default for Banking {
+    fn default() -> Self {
+        Self {
+            roll: 0.,
+            applied: 1.,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct AnimationResource {
+    /// How narrow a fully rolled ship gets, as a fraction of its width
+    pub max_bank_squash: f32,
+    /// How quickly ships roll into and out of turns, in full rolls per second
+    pub bank_rate: f32,
+}
+
+impl Default for AnimationResource {
+    fn default() -> Self {
+        Self {
+            max_bank_squash: 0.3,
+            bank_rate: 6.,
+        }
+    }
+}
+
+pub struct AnimationPlugin;
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationResource>()
+            .init_resource::<EntityPool<Animation>>()
+            .add_event::<AnimationFinishedEvent>()
+            .add_systems(
+                (
+                    Self::spawn_explosions,
+                    Self::wobble,
+                    Self::bank,
+                    Self::release_finished_explosions,
+                )
+                    .in_set(OnUpdate(GameState::Playing)),
+            )
+            .add_system(Self::recycle_explosions.in_base_set(CoreSet::PostUpdate))
+            .add_system(despawn_all::<Explosion>.in_schedule(OnExit(GameState::Playing)))
+            // After visual states, so animations win on entities that have both
+            .add_system(
+                Self::animate_sprites
+                    .in_base_set(CoreSet::PostUpdate)
+                    .after(VisualStateSystem),
+            );
+    }
+}
+
+impl AnimationPlugin {
+    fn spawn_explosions(
+        mut commands: Commands,
+        sprite_registry: Res<SpriteRegistry>,
+        mut explosion_pool: ResMut<EntityPool<Animation>>,
+        mut ev_enemy_destroyed: EventReader<EnemyDestroyedEvent>,
+    ) {
+        for ev in ev_enemy_destroyed.iter() {
+            let animation = Animation::Explosion;
+            let bundle = ExplosionBundle {
+                explosion: Explosion,
+                sprite: sprite_registry.sprite_sheet_bundle(
+                    animation.frames()[0],
+                    Transform::from_translation(ev.0.extend(0.)).with_scale(Vec3::splat(2.)),
+                ),
+                sprite_animation: SpriteAnimation::new(animation),
+                pooled: Pooled::active(),
+            };
+
+            let parked = std::iter::from_fn(|| explosion_pool.take(animation))
+                .find(|&entity| commands.get_entity(entity).is_some());
+            match parked {
+                Some(entity) => {
+                    commands.entity(entity).insert(bundle);
+                }
+                None => {
+                    commands.spawn(bundle);
+                }
+            }
+        }
+    }
+
+    fn animate_sprites(
+        mut query: Query<(Entity, &mut SpriteAnimation, &mut TextureAtlasSprite)>,
+        mut ev_finished: EventWriter<AnimationFinishedEvent>,
+        time: Res<Time>,
+    ) {
+        for (entity, mut sprite_animation, mut sprite) in query.iter_mut() {
+            if sprite_animation.finished {
+                continue;
+            }
+
+            sprite_animation.elapsed += time.delta_seconds();
+
+            let animation = sprite_animation.animation;
+            let length = animation.frames().len() as f32 / animation.fps();
+            if !animation.looping() && sprite_animation.elapsed >= length {
+                sprite_animation.finished = true;
+                ev_finished.send(AnimationFinishedEvent { entity, animation });
+            }
+
+            let index = animation.frames()[sprite_animation.frame()].index();
+            if sprite.index != index {
+                sprite.index = index;
+            }
+        }
+    }
+
+    fn release_finished_explosions(
+        mut ev_finished: EventReader<AnimationFinishedEvent>,
+        mut query: Query<&mut Pooled, With<Explosion>>,
+    ) {
+        for ev in ev_finished.iter() {
+            if let Ok(mut pooled) = query.get_mut(ev.entity) {
+                if pooled.is_active() {
+                    pooled.release();
+                }
+            }
+        }
+    }
+
+    /// Parks released explosions out of sight.
+    #[allow(clippy::type_complexity)]
+    fn recycle_explosions(
+        mut query: Query<
+            (Entity, &SpriteAnimation, &Pooled, &mut Visibility),
+            (With<Explosion>, Changed<Pooled>),
+        >,
+        mut explosion_pool: ResMut<EntityPool<Animation>>,
+    ) {
+        for (entity, sprite_animation, pooled, mut visibility) in query.iter_mut() {
+            if pooled.is_active() {
+                continue;
+            }
+
+            *visibility = Visibility::Hidden;
+            explosion_pool.park(sprite_animation.animation, entity);
+        }
+    }
+
+    fn wobble(mut query: Query<(&mut Wobble, &mut Transform)>, time: Res<Time>) {
+        let t = time.elapsed_seconds();
+
+        for (mut wobble, mut transform) in query.iter_mut() {
+            let angle = wobble.amplitude
+                * (t * wobble.frequency * std::f32::consts::TAU + wobble.phase).sin();
+
+            transform.rotate_z(angle - wobble.applied);
+            wobble.applied = angle;
+        }
+    }
+
+    fn bank(
+        mut query: Query<(&mut Banking, &Thrust, &mut Transform)>,
+        animation_resource: Res<AnimationResource>,
+        time: Res<Time>,
+    ) {
+        let max_change = animation_resource.bank_rate * time.delta_seconds();
+
+        for (mut banking, thrust, mut transform) in query.iter_mut() {
+            banking.roll += (thrust.direction.x - banking.roll).clamp(-max_change, max_change);
+
+            let squash = 1. - animation_resource.max_bank_squash * banking.roll.abs();
+            transform.scale.x *= squash / banking.applied;
+            banking.applied = squash;
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::{asset::HandleId, asset::LoadState, prelude::*, utils::HashMap};
+
+use crate::{music::MusicTrack, sfx::Sfx, sprites::SpriteSheet, GameState};
+
+/// The loop the player's engine hums with
+pub const ENGINE_PATH: &str = "Audio/spaceEngineSmall_000.ogg";
+
+/// The font every menu is written in
+pub const FONT_PATH: &str = "Fonts/DejaVuSansMono.ttf";
+
+/// Handles to everything the game loads, requested up front so that no system has to
+/// load by path while playing.
+#[derive(Resource)]
+pub struct GameAssets {
+    pub sprite_sheets: HashMap<SpriteSheet, Handle<Image>>,
+    /// Every variant of each sound effect
+    pub sfx: HashMap<Sfx, Vec<Handle<AudioSource>>>,
+    pub music: HashMap<MusicTrack, Handle<AudioSource>>,
+    pub music_layers: HashMap<MusicTrack, Handle<AudioSource>>,
+    pub engine: Handle<AudioSource>,
+    pub font: Handle<Font>,
+}
+
+impl FromWorld for GameAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        Self {
+            sprite_sheets: SpriteSheet::ALL
+                .iter()
+                .map(|&sheet| (sheet, asset_server.load(sheet.path())))
+                .collect(),
+            sfx: Sfx::ALL
+                .iter()
+                .map(|&sfx| {
+                    let variants = sfx.paths().map(|path| asset_server.load(path)).collect();
+                    (sfx, variants)
+                })
+                .collect(),
+            music: MusicTrack::ALL
+                .iter()
+                .map(|&track| (track, asset_server.load(track.path())))
+                .collect(),
+            music_layers: MusicTrack::ALL
+                .iter()
+                .filter_map(|&track| Some((track, asset_server.load(track.layer_path()?))))
+                .collect(),
+            engine: asset_server.load(ENGINE_PATH),
+            font: asset_server.load(FONT_PATH),
+        }
+    }
+}
+
+impl GameAssets {
+    /// Every path under `assets/` that the game loads.
+    pub fn paths() -> impl Iterator<Item = String> {
+        SpriteSheet::ALL
+            .iter()
+            .map(|sheet| sheet.path().to_owned())
+            .chain(Sfx::ALL.iter().flat_map(Sfx::paths))
+            .chain(MusicTrack::ALL.iter().map(|track| track.path().to_owned()))
+            .chain(
+                MusicTrack::ALL
+                    .iter()
+                    .filter_map(|track| Some(track.layer_path()?.to_owned())),
+            )
+            .chain([ENGINE_PATH.to_owned(), FONT_PATH.to_owned()])
+    }
+
+    pub fn sprite_sheet(&self, sheet: SpriteSheet) -> Handle<Image> {
+        self.sprite_sheets[&sheet].clone()
+    }
+
+    pub fn sfx(&self, sfx: Sfx) -> &[Handle<AudioSource>] {
+        &self.sfx[&sfx]
+    }
+
+    fn handle_ids(&self) -> impl Iterator<Item = HandleId> + '_ {
+        self.sprite_sheets
+            .values()
+            .map(|handle| handle.id())
+            .chain(self.sfx.values().flatten().map(|handle| handle.id()))
+            .chain(self.music.values().map(|handle| handle.id()))
+            .chain(self.music_layers.values().map(|handle| handle.id()))
+            .chain([self.engine.id(), self.font.id()])
+    }
+}
+
+/// How many of the [`GameAssets`] have finished loading.
+#[derive(Resource, Debug, Default)]
+pub struct LoadingProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl LoadingProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.
+        } else {
+            self.done as f32 / self.total as f32
+        }
+    }
+}
+
+/// Loads the [`GameAssets`] and moves on from [`GameState::Loading`] once they are ready.
+pub struct GameAssetsPlugin;
+impl Plugin for GameAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameAssets>()
+            .init_resource::<LoadingProgress>()
+            .add_system(Self::track_loading.in_set(OnUpdate(GameState::Loading)));
+    }
+}
+
+impl GameAssetsPlugin {
+    fn track_loading(
+        asset_server: Res<AssetServer>,
+        game_assets: Res<GameAssets>,
+        mut loading_progress: ResMut<LoadingProgress>,
+        mut next_state: ResMut<NextState<GameState>>,
+    ) {
+        let mut progress = LoadingProgress::default();
+        let mut failed = 0;
+
+        for handle_id in game_assets.handle_ids() {
+            progress.total += 1;
+
+            match asset_server.get_load_state(handle_id) {
+                LoadState::Loaded => progress.done += 1,
+                LoadState::Failed => {
+                    progress.done += 1;
+                    failed += 1;
+                }
+                _ => {}
+            }
+        }
+
+        if progress.done == progress.total {
+            if failed > 0 {
+                error!("{failed} of {} game assets failed to load", progress.total);
+            }
+
+            next_state.set(GameState::MainMenu);
+        }
+
+        *loading_progress = progress;
+    }
+}
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingBar;
+
+/// Shows a progress bar while in [`GameState::Loading`].
+pub struct LoadingScreenPlugin;
+impl Plugin for LoadingScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(Self::spawn_loading_screen.in_schedule(OnEnter(GameState::Loading)))
+            .add_system(Self::despawn_loading_screen.in_schedule(OnExit(GameState::Loading)))
+            .add_system(Self::update_loading_bar.in_set(OnUpdate(GameState::Loading)));
+    }
+}
+
+impl LoadingScreenPlugin {
+    fn spawn_loading_screen(mut commands: Commands) {
+        commands
+            .spawn((
+                LoadingScreen,
+                NodeBundle {
+                    style: Style {
+                        size: Size::width(Val::Percent(100.)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(60.), Val::Px(12.)),
+                            ..default()
+                        },
+                        background_color: Color::DARK_GRAY.into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            LoadingBar,
+                            NodeBundle {
+                                style: Style {
+                                    size: Size::new(Val::Percent(0.), Val::Percent(100.)),
+                                    ..default()
+                                },
+                                background_color: Color::WHITE.into(),
+                                ..default()
+                            },
+                        ));
+                    });
+            });
+    }
+
+    fn update_loading_bar(
+        loading_progress: Res<LoadingProgress>,
+        mut bar_query: Query<&mut Style, With<LoadingBar>>,
+    ) {
+        for mut style in bar_query.iter_mut() {
+            style.size.width = Val::Percent(loading_progress.fraction() * 100.);
+        }
+    }
+
+    fn despawn_loading_screen(
+        mut commands: Commands,
+        screen_query: Query<Entity, With<LoadingScreen>>,
+    ) {
+        for entity in screen_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
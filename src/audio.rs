@@ -0,0 +1,379 @@
+use std::{fs, io, path::Path};
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::GameAssets,
+    music::{MusicCue, MusicResource, MusicTrack},
+    player::Thrust,
+    sfx::{PlaySfxEvent, Sfx},
+};
+
+/// Where [`AudioSettings`] are kept between runs, relative to the working directory.
+pub const AUDIO_SETTINGS_PATH: &str = "audio_settings.ron";
+
+/// Groups of sounds that share a volume setting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AudioChannel {
+    Music,
+    Sfx,
+}
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Scales every channel, from 0 to 1
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.,
+            music_volume: 0.6,
+            sfx_volume: 0.8,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// The volume to play sounds on `channel` at, after applying the master volume.
+    pub fn volume(&self, channel: AudioChannel) -> f32 {
+        let channel_volume = match channel {
+            AudioChannel::Music => self.music_volume,
+            AudioChannel::Sfx => self.sfx_volume,
+        };
+
+        (self.master_volume * channel_volume).clamp(0., 1.)
+    }
+
+    /// Reads the settings saved at `path`, or the defaults if there are none or they can't
+    /// be read.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+
+        match fs::read_to_string(path) {
+            Ok(contents) => ron::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Ignoring unreadable audio settings in {path:?}: {err}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = ron::ser::to_string_pretty(self, default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(path, contents)
+    }
+}
+
+/// When the sound effects that are still playing started, to hold each [`Sfx`] to its
+/// [`Sfx::max_voices`].
+#[derive(Resource, Debug, Default)]
+pub struct SfxVoices {
+    started: HashMap<Sfx, Vec<f32>>,
+}
+
+impl SfxVoices {
+    /// Takes a voice for `sfx` at time `now` in seconds, if one is free.
+    pub fn try_start(&mut self, sfx: Sfx, now: f32) -> bool {
+        let started = self.started.entry(sfx).or_default();
+        started.retain(|&start| now - start < sfx.length());
+
+        if started.len() < sfx.max_voices() {
+            started.push(now);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Sound effects that are still playing, with the time to cut each one off at.
+#[derive(Resource, Default)]
+struct PlayingSfx(Vec<(Handle<AudioSink>, f32)>);
+
+/// The player's engine loop, while there is a player.
+#[derive(Resource, Default)]
+struct EngineSink(Option<Handle<AudioSink>>);
+
+/// A track that is playing, along with its intensity layer if it has one.
+struct PlayingTrack {
+    track: MusicTrack,
+    music: Handle<AudioSink>,
+    layer: Option<Handle<AudioSink>>,
+    /// How far the track has faded in, from 0 to 1
+    fade: f32,
+    /// Eases towards the cue's intensity, setting the volume of the layer relative to the
+    /// track, or how quickly a track without one plays
+    intensity: f32,
+}
+
+impl PlayingTrack {
+    fn apply_volume(&self, audio_sinks: &Assets<AudioSink>, volume: f32) {
+        if let Some(sink) = audio_sinks.get(&self.music) {
+            sink.set_volume(volume * self.fade);
+        }
+
+        if let Some(sink) = self.layer.as_ref().and_then(|layer| audio_sinks.get(layer)) {
+            sink.set_volume(volume * self.fade * self.intensity);
+        }
+    }
+
+    fn apply_speed(&self, audio_sinks: &Assets<AudioSink>, music_resource: &MusicResource) {
+        if self.layer.is_some() {
+            return;
+        }
+
+        if let Some(sink) = audio_sinks.get(&self.music) {
+            sink.set_speed(music_resource.speed(self.intensity));
+        }
+    }
+
+    fn stop(&self, audio_sinks: &Assets<AudioSink>) {
+        for handle in std::iter::once(&self.music).chain(&self.layer) {
+            if let Some(sink) = audio_sinks.get(handle) {
+                sink.stop();
+            }
+        }
+    }
+}
+
+/// The music [`GameAudioPlugin`] is playing, following the [`MusicCue`].
+#[derive(Resource, Default)]
+struct MusicPlayback {
+    current: Option<PlayingTrack>,
+    /// Tracks that were replaced and are still fading out
+    fading_out: Vec<PlayingTrack>,
+}
+
+/// Plays the music for the [`MusicCue`] and the sound effects requested with
+/// [`PlaySfxEvent`] at the volumes in [`AudioSettings`], which it loads from and saves to
+/// [`AUDIO_SETTINGS_PATH`].
+pub struct GameAudioPlugin;
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AudioSettings::load(AUDIO_SETTINGS_PATH))
+            .init_resource::<SfxVoices>()
+            .init_resource::<PlayingSfx>()
+            .init_resource::<MusicPlayback>()
+            .init_resource::<EngineSink>()
+            .add_system(Self::adjust_master_volume)
+            .add_system(Self::switch_music_track)
+            .add_system(
+                Self::fade_music
+                    .after(Self::switch_music_track)
+                    .after(Self::adjust_master_volume),
+            )
+            .add_system(Self::save_settings.after(Self::adjust_master_volume))
+            .add_system(Self::play_engine.after(Self::adjust_master_volume))
+            .add_system(Self::play_sfx.in_base_set(CoreSet::PostUpdate))
+            .add_system(Self::cut_off_sfx.in_base_set(CoreSet::PostUpdate));
+    }
+}
+
+impl GameAudioPlugin {
+    fn adjust_master_volume(
+        keyboard: Res<Input<KeyCode>>,
+        mut audio_settings: ResMut<AudioSettings>,
+    ) {
+        let step = if keyboard.just_pressed(KeyCode::Minus) {
+            -0.1
+        } else if keyboard.just_pressed(KeyCode::Equals) {
+            0.1
+        } else {
+            return;
+        };
+
+        audio_settings.master_volume = (audio_settings.master_volume + step).clamp(0., 1.);
+    }
+
+    /// Starts the cued track, silent until [`Self::fade_music`] brings it in.
+    fn switch_music_track(
+        game_assets: Res<GameAssets>,
+        audio: Res<Audio>,
+        audio_sinks: Res<Assets<AudioSink>>,
+        music_cue: Res<MusicCue>,
+        mut music_playback: ResMut<MusicPlayback>,
+    ) {
+        let track = music_cue.track;
+        let play = |source: &Handle<AudioSource>| {
+            audio.play_with_settings(source.clone(), PlaybackSettings::LOOP.with_volume(0.))
+        };
+
+        if let Some(playing) = &mut music_playback.current {
+            if playing.track == track {
+                return;
+            }
+
+            // Tracks sharing a piece of music carry on with it instead of starting it over,
+            // only swapping the layer
+            if playing.track.path() == track.path() {
+                let replaced_layer = playing.layer.take();
+                if let Some(sink) = replaced_layer.and_then(|layer| audio_sinks.get(&layer)) {
+                    sink.stop();
+                }
+
+                playing.track = track;
+                playing.layer = game_assets.music_layers.get(&track).map(play);
+                // A new layer fades in from silence, while the music keeps its pace
+                if playing.layer.is_some() {
+                    playing.intensity = 0.;
+                }
+                return;
+            }
+        }
+
+        let replaced = music_playback.current.replace(PlayingTrack {
+            track,
+            music: play(&game_assets.music[&track]),
+            layer: game_assets.music_layers.get(&track).map(play),
+            fade: 0.,
+            intensity: 0.,
+        });
+        music_playback.fading_out.extend(replaced);
+    }
+
+    /// Crossfades from the replaced tracks to the current one and eases its intensity
+    /// towards the cue.
+    fn fade_music(
+        audio_sinks: Res<Assets<AudioSink>>,
+        audio_settings: Res<AudioSettings>,
+        music_cue: Res<MusicCue>,
+        music_resource: Res<MusicResource>,
+        time: Res<Time>,
+        mut music_playback: ResMut<MusicPlayback>,
+    ) {
+        let music_playback = &mut *music_playback;
+        let fade_step = if music_resource.crossfade > 0. {
+            time.delta_seconds() / music_resource.crossfade
+        } else {
+            1.
+        };
+        let volume = audio_settings.volume(AudioChannel::Music);
+
+        if let Some(playing) = &mut music_playback.current {
+            playing.fade = (playing.fade + fade_step).min(1.);
+            playing.intensity +=
+                (music_cue.intensity - playing.intensity).clamp(-fade_step, fade_step);
+            playing.apply_volume(&audio_sinks, volume);
+            playing.apply_speed(&audio_sinks, &music_resource);
+        }
+
+        music_playback.fading_out.retain_mut(|playing| {
+            playing.fade = (playing.fade - fade_step).max(0.);
+            playing.apply_volume(&audio_sinks, volume);
+
+            if playing.fade > 0. {
+                true
+            } else {
+                playing.stop(&audio_sinks);
+                false
+            }
+        });
+    }
+
+    fn save_settings(audio_settings: Res<AudioSettings>) {
+        // Only save what the player changed, not the settings just loaded
+        if !audio_settings.is_changed() || audio_settings.is_added() {
+            return;
+        }
+
+        if let Err(err) = audio_settings.save(AUDIO_SETTINGS_PATH) {
+            warn!("Couldn't save audio settings to {AUDIO_SETTINGS_PATH}: {err}");
+        }
+    }
+
+    fn play_sfx(
+        game_assets: Res<GameAssets>,
+        audio: Res<Audio>,
+        audio_settings: Res<AudioSettings>,
+        time: Res<Time>,
+        mut sfx_voices: ResMut<SfxVoices>,
+        mut playing_sfx: ResMut<PlayingSfx>,
+        mut ev_sfx: EventReader<PlaySfxEvent>,
+    ) {
+        let volume = audio_settings.volume(AudioChannel::Sfx);
+        let now = time.elapsed_seconds();
+
+        for ev in ev_sfx.iter() {
+            if !sfx_voices.try_start(ev.0, now) {
+                continue;
+            }
+
+            let variants = game_assets.sfx(ev.0);
+            let variant = variants[fastrand::usize(..variants.len())].clone();
+
+            let sink =
+                audio.play_with_settings(variant, PlaybackSettings::ONCE.with_volume(volume));
+            playing_sfx.0.push((sink, now + ev.0.length()));
+        }
+    }
+
+    fn cut_off_sfx(
+        audio_sinks: Res<Assets<AudioSink>>,
+        time: Res<Time>,
+        mut playing_sfx: ResMut<PlayingSfx>,
+    ) {
+        let now = time.elapsed_seconds();
+
+        playing_sfx.0.retain(|(handle, end)| {
+            if now < *end {
+                return true;
+            }
+
+            if let Some(sink) = audio_sinks.get(handle) {
+                sink.stop();
+            }
+            false
+        });
+    }
+
+    /// Hums louder and higher the faster the player goes.
+    fn play_engine(
+        game_assets: Res<GameAssets>,
+        audio: Res<Audio>,
+        audio_sinks: Res<Assets<AudioSink>>,
+        audio_settings: Res<AudioSettings>,
+        mut engine_sink: ResMut<EngineSink>,
+        player_query: Query<&Thrust>,
+        time: Res<Time>,
+    ) {
+        let Ok(thrust) = player_query.get_single() else {
+            if let Some(sink) = engine_sink
+                .0
+                .take()
+                .and_then(|handle| audio_sinks.get(&handle))
+            {
+                sink.stop();
+            }
+            return;
+        };
+
+        let Some(handle) = &engine_sink.0 else {
+            engine_sink.0 = Some(audio.play_with_settings(
+                game_assets.engine.clone(),
+                PlaybackSettings::LOOP.with_volume(0.),
+            ));
+            return;
+        };
+
+        if let Some(sink) = audio_sinks.get(handle) {
+            // Idles quietly rather than cutting out when the player stops, and falls silent
+            // while the game is paused
+            let level = if time.is_paused() {
+                0.
+            } else {
+                0.3 + 0.7 * thrust.throttle
+            };
+
+            sink.set_volume(audio_settings.volume(AudioChannel::Sfx) * 0.4 * level);
+            sink.set_speed(0.8 + 0.4 * thrust.throttle);
+        }
+    }
+}
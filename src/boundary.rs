@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use crate::{
+    movement::{MovementSystem, Velocity},
+    play_area::PlayArea,
+    pool::{despawn_or_release, Pooled},
+    GameState,
+};
+
+/// What happens to an entity whose center leaves the [`PlayArea`].
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundaryBehavior {
+    /// Reappear at the opposite edge
+    Wrap,
+    /// Stop at the edge
+    Clamp,
+    /// Stop at the edge and reverse direction
+    Bounce,
+    Despawn,
+}
+
+/// Systems that apply each [`BoundaryBehavior`], right after movement.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BoundarySystem;
+
+pub struct BoundaryPlugin;
+impl Plugin for BoundaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_set(
+            BoundarySystem
+                .after(MovementSystem)
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_system(Self::enforce_boundaries.in_set(BoundarySystem));
+    }
+}
+
+impl BoundaryPlugin {
+    #[allow(clippy::type_complexity)]
+    fn enforce_boundaries(
+        mut commands: Commands,
+        mut query: Query<(
+            Entity,
+            &BoundaryBehavior,
+            &mut Transform,
+            Option<&mut Velocity>,
+            Option<&mut Pooled>,
+        )>,
+        play_area: Res<PlayArea>,
+    ) {
+        let half_extents = Vec2::new(play_area.half_width(), play_area.half_height());
+
+        for (entity, boundary_behavior, mut transform, mut velocity, pooled) in query.iter_mut() {
+            let position = transform.translation.truncate();
+            // Which side of the play area the entity is past along each axis, if any
+            let overshoot = Vec2::select(
+                position.abs().cmpgt(half_extents),
+                position.signum(),
+                Vec2::ZERO,
+            );
+
+            if overshoot == Vec2::ZERO {
+                continue;
+            }
+
+            match boundary_behavior {
+                BoundaryBehavior::Wrap => {
+                    // Land just inside the opposite edge, so the entity isn't wrapped straight back
+                    let wrapped = -overshoot * (half_extents - 1.);
+                    let position = Vec2::select(overshoot.cmpne(Vec2::ZERO), wrapped, position);
+                    transform.translation = position.extend(transform.translation.z);
+                }
+                BoundaryBehavior::Clamp | BoundaryBehavior::Bounce => {
+                    let position = position.clamp(-half_extents, half_extents);
+                    transform.translation = position.extend(transform.translation.z);
+
+                    if let Some(velocity) = velocity.as_mut() {
+                        for axis in 0..2 {
+                            // Only turn around velocity that still points out of the play area
+                            if overshoot[axis] != 0. && velocity[axis].signum() == overshoot[axis] {
+                                velocity[axis] = if *boundary_behavior == BoundaryBehavior::Bounce {
+                                    -velocity[axis]
+                                } else {
+                                    0.
+                                };
+                            }
+                        }
+                    }
+                }
+                BoundaryBehavior::Despawn => despawn_or_release(&mut commands, entity, pooled),
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+
+use crate::{
+    display::MainCamera,
+    enemy::{EnemyDestroyedEvent, Health},
+    visual_state::VisualStateSystem,
+};
+
+/// Shakes the camera, adding `0.0` (nothing) to `1.0` (as hard as it goes) of trauma.
+///
+/// Trauma adds up across events and wears off over time.
+pub struct CameraShakeEvent(pub f32);
+
+#[derive(Resource, Clone, Debug)]
+pub struct CameraEffectsSettings {
+    /// Turns every effect off, for players who'd rather not have them
+    pub enabled: bool,
+    /// Scales screen shake and hit-stop, from 0 (off) to 1
+    pub intensity: f32,
+    /// How far the camera moves at full trauma, in world units
+    pub max_shake_offset: f32,
+    /// How far the camera turns at full trauma, in radians
+    pub max_shake_angle: f32,
+    /// How much trauma wears off per second
+    pub trauma_decay: f32,
+    /// How long the game nearly freezes after a kill, in real seconds
+    pub hit_stop_duration: f32,
+    /// How fast the game runs during hit-stop at full intensity, relative to normal
+    pub hit_stop_speed: f32,
+    /// Flash sprites when they take damage
+    pub flash: bool,
+    /// How long a damage flash lasts, in seconds
+    pub flash_duration: f32,
+    /// Multiplies the sprite's texture while flashing; past 1 it washes the sprite out
+    pub flash_color: Color,
+}
+
+impl Default for CameraEffectsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            intensity: 1.,
+            max_shake_offset: 12.,
+            max_shake_angle: 0.05,
+            trauma_decay: 1.5,
+            hit_stop_duration: 0.05,
+            hit_stop_speed: 0.05,
+            flash: true,
+            flash_duration: 0.08,
+            flash_color: Color::rgb(4., 4., 4.),
+        }
+    }
+}
+
+impl CameraEffectsSettings {
+    fn effective_intensity(&self) -> f32 {
+        if self.enabled {
+            self.intensity.clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+}
+
+/// Trauma built up from [`CameraShakeEvent`]s, and the shake it has applied to the camera.
+#[derive(Resource, Default)]
+struct ScreenShake {
+    trauma: f32,
+    offset: Vec2,
+    angle: f32,
+}
+
+/// Real time left until the game runs at full speed again.
+#[derive(Resource, Default)]
+struct HitStop(f32);
+
+/// Shows the sprite in the flash color until the timer runs out.
+#[derive(Component)]
+struct DamageFlash {
+    timer: Timer,
+    color_before: Color,
+}
+
+/// Screen shake, hit-stop and damage flashes, to make hits feel like they land.
+pub struct CameraEffectsPlugin;
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraEffectsSettings>()
+            .init_resource::<ScreenShake>()
+            .init_resource::<HitStop>()
+            .add_system(Self::shake_camera)
+            .add_system(Self::hit_stop_on_kills)
+            .add_systems(
+                (Self::flash_damaged_sprites, Self::fade_damage_flashes)
+                    .chain()
+                    .in_base_set(CoreSet::PostUpdate)
+                    .after(VisualStateSystem),
+            );
+    }
+}
+
+impl CameraEffectsPlugin {
+    fn shake_camera(
+        mut screen_shake: ResMut<ScreenShake>,
+        mut ev_shake: EventReader<CameraShakeEvent>,
+        mut camera_query: Query<&mut Transform, With<MainCamera>>,
+        settings: Res<CameraEffectsSettings>,
+        time: Res<Time>,
+    ) {
+        let added: f32 = ev_shake.iter().map(|ev| ev.0).sum();
+        // Real time, so shake still settles during hit-stop
+        let decayed = screen_shake.trauma - settings.trauma_decay * time.raw_delta_seconds();
+        screen_shake.trauma = (decayed + added).clamp(0., 1.);
+
+        // Squaring keeps light hits subtle and heavy ones violent
+        let shake = screen_shake.trauma.powi(2) * settings.effective_intensity();
+        let t = time.raw_elapsed_seconds();
+        let offset = Vec2::new((t * 37.).sin(), (t * 43. + 1.).sin()) * settings.max_shake_offset;
+        let offset = offset * shake;
+        let angle = (t * 29. + 2.).sin() * settings.max_shake_angle * shake;
+
+        for mut transform in camera_query.iter_mut() {
+            transform.translation += (offset - screen_shake.offset).extend(0.);
+            transform.rotate_z(angle - screen_shake.angle);
+        }
+
+        screen_shake.offset = offset;
+        screen_shake.angle = angle;
+    }
+
+    fn hit_stop_on_kills(
+        mut hit_stop: ResMut<HitStop>,
+        mut ev_enemy_destroyed: EventReader<EnemyDestroyedEvent>,
+        settings: Res<CameraEffectsSettings>,
+        mut time: ResMut<Time>,
+    ) {
+        let intensity = settings.effective_intensity();
+
+        if ev_enemy_destroyed.iter().count() > 0 && intensity > 0. {
+            hit_stop.0 = settings.hit_stop_duration;
+            time.set_relative_speed(1. + (settings.hit_stop_speed - 1.) * intensity);
+        } else if hit_stop.0 > 0. {
+            hit_stop.0 -= time.raw_delta_seconds();
+
+            if hit_stop.0 <= 0. {
+                time.set_relative_speed(1.);
+            }
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn flash_damaged_sprites(
+        mut commands: Commands,
+        mut damaged_query: Query<
+            (
+                Entity,
+                Ref<Health>,
+                &TextureAtlasSprite,
+                Option<&mut DamageFlash>,
+            ),
+            Changed<Health>,
+        >,
+        settings: Res<CameraEffectsSettings>,
+    ) {
+        if !settings.enabled || !settings.flash {
+            return;
+        }
+
+        for (entity, health, sprite, flash) in damaged_query.iter_mut() {
+            // Spawning with full health isn't damage
+            if health.is_added() {
+                continue;
+            }
+
+            match flash {
+                Some(mut flash) => flash.timer.reset(),
+                None => {
+                    commands.entity(entity).insert(DamageFlash {
+                        timer: Timer::from_seconds(settings.flash_duration, TimerMode::Once),
+                        color_before: sprite.color,
+                    });
+                }
+            }
+        }
+    }
+
+    fn fade_damage_flashes(
+        mut commands: Commands,
+        mut flash_query: Query<(Entity, &mut DamageFlash, &mut TextureAtlasSprite)>,
+        settings: Res<CameraEffectsSettings>,
+        time: Res<Time>,
+    ) {
+        for (entity, mut flash, mut sprite) in flash_query.iter_mut() {
+            if flash.timer.tick(time.delta()).finished() {
+                sprite.color = flash.color_before;
+                commands.entity(entity).remove::<DamageFlash>();
+            } else {
+                sprite.color = settings.flash_color;
+            }
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use bevy::prelude::*;
+
+use crate::{enemy::Waves, lives::Respawn, player::Player, GameState};
+
+/// How hard the enemies push back, chosen on the title screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// Hard, with less to fall back on
+    Arcade,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Arcade,
+            Difficulty::Arcade => Difficulty::Easy,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Arcade,
+            Difficulty::Normal => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Normal,
+            Difficulty::Arcade => Difficulty::Hard,
+        }
+    }
+
+    /// How the preset scales the base tuning in the enemy and player resources.
+    pub fn scaling(self) -> DifficultyScaling {
+        match self {
+            Difficulty::Easy => DifficultyScaling {
+                enemy_speed: 0.75,
+                enemy_health: 0.75,
+                enemy_fire_rate: 0.5,
+                spawn_intensity: 0.75,
+                extra_bombs: 2,
+                extra_evades: 2,
+            },
+            Difficulty::Normal => DifficultyScaling::default(),
+            Difficulty::Hard => DifficultyScaling {
+                enemy_speed: 1.25,
+                enemy_health: 1.5,
+                enemy_fire_rate: 1.5,
+                spawn_intensity: 1.25,
+                extra_bombs: -1,
+                extra_evades: -1,
+            },
+            Difficulty::Arcade => DifficultyScaling {
+                enemy_speed: 1.5,
+                enemy_health: 2.,
+                enemy_fire_rate: 2.,
+                spawn_intensity: 1.5,
+                extra_bombs: -2,
+                extra_evades: -2,
+            },
+        }
+    }
+}
+
+/// What the current difficulty does to the game, kept up to date by the
+/// [`DifficultyPlugin`] for the systems that spawn enemies and start runs.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct DifficultyScaling {
+    /// Multiplies the speed of enemies
+    pub enemy_speed: f32,
+    /// Multiplies the health of enemies
+    pub enemy_health: f32,
+    /// Multiplies how often enemies fire
+    pub enemy_fire_rate: f32,
+    /// Multiplies how many enemies each wave brings
+    pub spawn_intensity: f32,
+    /// Added to the bombs the player starts each run with
+    pub extra_bombs: i32,
+    /// Added to the evades the player starts each run with
+    pub extra_evades: i32,
+}
+
+impl Default for DifficultyScaling {
+    fn default() -> Self {
+        Self {
+            enemy_speed: 1.,
+            enemy_health: 1.,
+            enemy_fire_rate: 1.,
+            spawn_intensity: 1.,
+            extra_bombs: 0,
+            extra_evades: 0,
+        }
+    }
+}
+
+impl DifficultyScaling {
+    /// Adds `extra` to a starting count of `base`, stopping at none.
+    pub fn starting_count(base: u32, extra: i32) -> u32 {
+        base.saturating_add_signed(extra)
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct DifficultySettings {
+    pub difficulty: Difficulty,
+    /// Adjusts how many enemies come at once to how well the player is doing
+    pub adaptive: bool,
+}
+
+#[derive(Resource)]
+pub struct DifficultyResource {
+    /// How much the adaptive intensity rises for each wave cleared without losing a life,
+    /// and falls for each life lost
+    pub intensity_step: f32,
+    pub min_intensity: f32,
+    pub max_intensity: f32,
+}
+
+impl Default for DifficultyResource {
+    fn default() -> Self {
+        Self {
+            intensity_step: 0.1,
+            min_intensity: 0.5,
+            max_intensity: 1.5,
+        }
+    }
+}
+
+/// How the player has been doing lately, for the adaptive difficulty.
+#[derive(Resource, Debug)]
+pub struct AdaptiveDifficulty {
+    /// Multiplies the spawn intensity of the preset, starting at 1
+    pub intensity: f32,
+    /// The last wave whose outcome was counted
+    judged_wave: u32,
+    lives_lost: u32,
+}
+
+impl Default for AdaptiveDifficulty {
+    fn default() -> Self {
+        Self {
+            intensity: 1.,
+            judged_wave: 0,
+            lives_lost: 0,
+        }
+    }
+}
+
+/// Keeps the [`DifficultyScaling`] up to date. Systems reading it at the start of a run order
+/// themselves after this, so they see the run's own scaling.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DifficultySystem;
+
+/// Scales enemies and the player's starting kit by the chosen [`Difficulty`], and adapts
+/// how many enemies come at once when [`DifficultySettings::adaptive`] is on.
+pub struct DifficultyPlugin;
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DifficultySettings>()
+            .init_resource::<DifficultyResource>()
+            .init_resource::<AdaptiveDifficulty>()
+            .init_resource::<DifficultyScaling>()
+            .add_systems(
+                (Self::reset_adaptive_difficulty, Self::update_scaling)
+                    .chain()
+                    .in_set(DifficultySystem)
+                    .in_schedule(OnEnter(GameState::Playing)),
+            )
+            .add_system(
+                Self::adapt_to_performance
+                    .before(DifficultySystem)
+                    .in_set(OnUpdate(GameState::Playing)),
+            )
+            .add_system(Self::update_scaling.in_set(DifficultySystem));
+    }
+}
+
+impl DifficultyPlugin {
+    fn reset_adaptive_difficulty(mut adaptive_difficulty: ResMut<AdaptiveDifficulty>) {
+        *adaptive_difficulty = AdaptiveDifficulty::default();
+    }
+
+    /// Raises the intensity for each wave cleared without losing a life, and lowers it for
+    /// each life lost.
+    fn adapt_to_performance(
+        mut adaptive_difficulty: ResMut<AdaptiveDifficulty>,
+        difficulty_settings: Res<DifficultySettings>,
+        difficulty_resource: Res<DifficultyResource>,
+        waves: Res<Waves>,
+        respawn_query: Query<(), (With<Player>, Added<Respawn>)>,
+    ) {
+        if !difficulty_settings.adaptive {
+            return;
+        }
+
+        let mut intensity = adaptive_difficulty.intensity;
+
+        if !respawn_query.is_empty() {
+            adaptive_difficulty.lives_lost += 1;
+            intensity -= difficulty_resource.intensity_step;
+        }
+
+        // Judged as the wave is cleared, so the next one already reflects it
+        if waves.is_warping() && adaptive_difficulty.judged_wave != waves.wave {
+            if adaptive_difficulty.lives_lost == 0 {
+                intensity += difficulty_resource.intensity_step;
+            }
+            adaptive_difficulty.judged_wave = waves.wave;
+            adaptive_difficulty.lives_lost = 0;
+        }
+
+        intensity = intensity.clamp(
+            difficulty_resource.min_intensity,
+            difficulty_resource.max_intensity,
+        );
+        if adaptive_difficulty.intensity != intensity {
+            adaptive_difficulty.intensity = intensity;
+        }
+    }
+
+    fn update_scaling(
+        difficulty_settings: Res<DifficultySettings>,
+        adaptive_difficulty: Res<AdaptiveDifficulty>,
+        mut difficulty_scaling: ResMut<DifficultyScaling>,
+    ) {
+        if !difficulty_settings.is_changed() && !adaptive_difficulty.is_changed() {
+            return;
+        }
+
+        let mut scaling = difficulty_settings.difficulty.scaling();
+        if difficulty_settings.adaptive {
+            scaling.spawn_intensity *= adaptive_difficulty.intensity;
+        }
+
+        *difficulty_scaling = scaling;
+    }
+}
@@ -0,0 +1,186 @@
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*,
+    render::{
+        camera::{ScalingMode, Viewport},
+        view::RenderLayers,
+    },
+    window::{PrimaryWindow, WindowMode},
+};
+
+/// Marks the camera that renders the game, as opposed to the one drawing the letterbox bars.
+#[derive(Component)]
+pub struct MainCamera;
+
+#[derive(Component)]
+struct LetterboxCamera;
+
+/// How the game window is presented on screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub fn next(self) -> Self {
+        match self {
+            DisplayMode::Windowed => DisplayMode::Borderless,
+            DisplayMode::Borderless => DisplayMode::Fullscreen,
+            DisplayMode::Fullscreen => DisplayMode::Windowed,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            DisplayMode::Windowed => DisplayMode::Fullscreen,
+            DisplayMode::Borderless => DisplayMode::Windowed,
+            DisplayMode::Fullscreen => DisplayMode::Borderless,
+        }
+    }
+}
+
+impl From<DisplayMode> for WindowMode {
+    fn from(mode: DisplayMode) -> Self {
+        match mode {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct DisplaySettings {
+    /// The size of the world area the camera always shows, in world units
+    pub virtual_resolution: Vec2,
+    /// Only scale the virtual resolution by whole numbers, which keeps pixel art crisp at
+    /// the cost of wider bars
+    pub integer_scaling: bool,
+    pub mode: DisplayMode,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            virtual_resolution: Vec2::new(500., 500.),
+            integer_scaling: false,
+            mode: DisplayMode::Windowed,
+        }
+    }
+}
+
+/// Keeps the virtual resolution fully visible at any window size, filling the leftover
+/// space with black bars.
+pub struct DisplayPlugin;
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DisplaySettings>()
+            .add_startup_system(Self::spawn_letterbox_camera)
+            .add_system(Self::cycle_display_mode)
+            .add_system(Self::apply_display_mode.after(Self::cycle_display_mode))
+            .add_system(Self::fit_camera_to_window);
+    }
+}
+
+impl DisplayPlugin {
+    fn spawn_letterbox_camera(mut commands: Commands) {
+        // Renders nothing but its clear color behind the main camera's viewport
+        commands.spawn((
+            LetterboxCamera,
+            Camera2dBundle {
+                camera: Camera {
+                    order: -1,
+                    ..default()
+                },
+                camera_2d: Camera2d {
+                    clear_color: ClearColorConfig::Custom(Color::BLACK),
+                },
+                ..default()
+            },
+            RenderLayers::layer(RenderLayers::TOTAL_LAYERS as u8 - 1),
+            UiCameraConfig { show_ui: false },
+        ));
+    }
+
+    fn cycle_display_mode(
+        keyboard: Res<Input<KeyCode>>,
+        mut display_settings: ResMut<DisplaySettings>,
+    ) {
+        if keyboard.just_pressed(KeyCode::F11) {
+            display_settings.mode = display_settings.mode.next();
+        }
+    }
+
+    fn apply_display_mode(
+        display_settings: Res<DisplaySettings>,
+        mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    ) {
+        if !display_settings.is_changed() {
+            return;
+        }
+
+        if let Ok(mut window) = window_query.get_single_mut() {
+            window.mode = display_settings.mode.into();
+        }
+    }
+
+    fn fit_camera_to_window(
+        display_settings: Res<DisplaySettings>,
+        window_query: Query<&Window, With<PrimaryWindow>>,
+        mut camera_query: Query<(&mut Camera, &mut OrthographicProjection), With<MainCamera>>,
+    ) {
+        let Ok(window) = window_query.get_single() else {
+            return;
+        };
+
+        let window_size = UVec2::new(window.physical_width(), window.physical_height());
+
+        // A minimized window has no area to fit into
+        if window_size.x == 0 || window_size.y == 0 {
+            return;
+        }
+
+        let virtual_resolution = display_settings.virtual_resolution;
+        let mut scale = (window_size.x as f32 / virtual_resolution.x)
+            .min(window_size.y as f32 / virtual_resolution.y);
+
+        if display_settings.integer_scaling && scale >= 1. {
+            scale = scale.floor();
+        }
+
+        let viewport_size = (virtual_resolution * scale)
+            .as_uvec2()
+            .min(window_size)
+            .max(UVec2::ONE);
+        let viewport_position = (window_size - viewport_size) / 2;
+
+        for (mut camera, mut projection) in camera_query.iter_mut() {
+            let up_to_date = camera.viewport.as_ref().is_some_and(|viewport| {
+                viewport.physical_position == viewport_position
+                    && viewport.physical_size == viewport_size
+            });
+
+            if !up_to_date {
+                camera.viewport = Some(Viewport {
+                    physical_position: viewport_position,
+                    physical_size: viewport_size,
+                    ..default()
+                });
+            }
+
+            if !matches!(
+                projection.scaling_mode,
+                ScalingMode::Fixed { width, height }
+                    if width == virtual_resolution.x && height == virtual_resolution.y
+            ) {
+                projection.scaling_mode = ScalingMode::Fixed {
+                    width: virtual_resolution.x,
+                    height: virtual_resolution.y,
+                };
+            }
+        }
+    }
+}
@@ -1,24 +1,80 @@
-use bevy::{prelude::*, window::PrimaryWindow};
+use bevy::{ecs::system::SystemParam, prelude::*};
 
 use crate::{
-    bomb::{Bomb, BombResource},
-    bullet::{Bullet, BulletResource},
+    animation::Wobble,
+    boundary::BoundaryBehavior,
+    camera_effects::CameraShakeEvent,
+    despawn_all,
+    difficulty::{DifficultyScaling, DifficultySystem},
+    evade::EvadeTimer,
+    lives::Invulnerable,
+    movement::Velocity,
+    player::{Player, PlayerResource},
+    projectile::{FireProjectileEvent, ProjectileKind, ProjectileModifiers, ProjectileOwner},
+    sfx::{PlaySfxEvent, Sfx},
+    sprites::{SpriteName, SpriteRegistry},
+    visual_state::{VisualState, VisualStates},
+    GameState,
 };
 
 #[derive(Component)]
-struct Enemy;
+pub struct Enemy;
+
+/// Sent with the position of an enemy when it is shot down.
+pub struct EnemyDestroyedEvent(pub Vec2);
+
+/// Hit points left; the entity is destroyed when they run out.
+#[derive(Component, Debug)]
+pub struct Health(pub f32);
 
 #[derive(Bundle)]
 struct EnemyBundle {
     enemy: Enemy,
+    health: Health,
     #[bundle]
-    sprite: SpriteBundle,
+    sprite: SpriteSheetBundle,
+    velocity: Velocity,
+    wobble: Wobble,
+    boundary_behavior: BoundaryBehavior,
 }
 
 #[derive(Resource)]
-struct EnemyResource {
-    speed: f32,
-    health: f32,
+pub struct EnemyResource {
+    /// The distance an enemy travels per second
+    pub speed: f32,
+    pub health: f32,
+    /// The radius of the enemy (used for collision detection with the player)
+    pub radius: f32,
+    /// The health the player loses when colliding with an enemy
+    pub contact_damage: f32,
+    /// How many shots per second each enemy fires down at the player (0 for none)
+    pub fire_rate: f32,
+    /// How long the player warps for between clearing a wave and the next one arriving, in
+    /// seconds
+    pub wave_delay: f32,
+    /// Each wave brings one more enemy than the last, up to this many
+    pub max_wave_size: u32,
+    /// The space between the enemies of a wave
+    pub wave_spacing: f32,
+}
+
+/// Fires an enemy's shots whenever the timer goes off.
+#[derive(Component, Debug)]
+pub struct EnemyGun(pub Timer);
+
+/// The wave the player is on, and the warp to the next one once it's cleared.
+#[derive(Resource, Debug, Default)]
+pub struct Waves {
+    /// Counting from 1 for the first wave
+    pub wave: u32,
+    warp: Option<Timer>,
+}
+
+impl Waves {
+    /// Between waves, with the next one on its way.
+    pub fn is_warping(&self) -> bool {
+        self.warp.is_some()
+    }
 }
 
 pub struct EnemyPlugin;
@@ -27,110 +83,169 @@ impl Plugin for EnemyPlugin {
         app.insert_resource(EnemyResource {
             speed: 200.,
             health: 100.,
+            radius: 20.,
+            contact_damage: 25.,
+            fire_rate: 0.25,
+            wave_delay: 4.,
+            max_wave_size: 8,
+            wave_spacing: 60.,
         })
-        .add_startup_system(Self::spawn_single)
-        .add_system(Self::move_horizontal)
-        .add_system(Self::wrap_enemy_around_window)
-        .add_system(Self::check_bullet_collision)
-        .add_system(Self::check_bomb_collision);
+        .init_resource::<Waves>()
+        .add_event::<EnemyDestroyedEvent>()
+        .add_system(
+            Self::start_first_wave
+                .after(DifficultySystem)
+                .in_schedule(OnEnter(GameState::Playing)),
+        )
+        .add_systems(
+            (
+                Self::advance_waves.after(DifficultySystem),
+                Self::fire_at_player,
+                Self::check_player_collision,
+            )
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_system(despawn_all::<Enemy>.in_schedule(OnExit(GameState::Playing)));
     }
 }
 
-impl EnemyPlugin {
-    fn spawn_single(mut commands: Commands, asset_server: Res<AssetServer>) {
-        let enemy_sprite = SpriteBundle {
-            transform: Transform {
-                translation: Vec3::new(0., 200., 0.),
-                rotation: Quat::from_rotation_z(90_f32.to_radians()),
-                ..default()
-            },
-            texture: asset_server.load("Ships/ship_0009.png"),
-            ..default()
-        };
-
-        let enemy_bundle = EnemyBundle {
-            enemy: Enemy,
-            sprite: enemy_sprite,
-        };
+/// What spawning a wave of enemies needs, scaled by the difficulty.
+#[derive(SystemParam)]
+struct WaveSpawner<'w> {
+    sprite_registry: Res<'w, SpriteRegistry>,
+    enemy_resource: Res<'w, EnemyResource>,
+    difficulty_scaling: Res<'w, DifficultyScaling>,
+}
 
-        commands.spawn(enemy_bundle);
+impl WaveSpawner<'_> {
+    /// Spawns a row of enemies for `wave`, centred at the top of the play area.
+    fn spawn(&self, commands: &mut Commands, wave: u32) {
+        let enemy_resource = &self.enemy_resource;
+        let scaling = &self.difficulty_scaling;
+        let count = ((wave as f32 * scaling.spawn_intensity).round() as u32)
+            .clamp(1, enemy_resource.max_wave_size.max(1));
+        let fire_rate = enemy_resource.fire_rate * scaling.enemy_fire_rate;
+
+        for i in 0..count {
+            let x = (i as f32 - (count - 1) as f32 / 2.) * enemy_resource.wave_spacing;
+            let enemy_sprite = self.sprite_registry.sprite_sheet_bundle(
+                SpriteName::EnemyShip,
+                Transform {
+                    translation: Vec3::new(x, 200., 0.),
+                    rotation: Quat::from_rotation_z(90_f32.to_radians()),
+                    ..default()
+                },
+            );
+
+            let enemy_bundle = EnemyBundle {
+                enemy: Enemy,
+                health: Health(enemy_resource.health * scaling.enemy_health),
+                sprite: enemy_sprite,
+                velocity: Velocity(Vec2::new(-enemy_resource.speed * scaling.enemy_speed, 0.)),
+                wobble: Wobble::new(0.15, 1.5),
+                boundary_behavior: BoundaryBehavior::Wrap,
+            };
+
+            let mut enemy = commands.spawn(enemy_bundle);
+            if fire_rate > 0. {
+                enemy.insert(EnemyGun(Timer::from_seconds(
+                    1. / fire_rate,
+                    TimerMode::Repeating,
+                )));
+            }
+        }
     }
+}
 
-    fn move_horizontal(
-        mut enemy_query: Query<&mut Transform, With<Enemy>>,
-        enemy_resource: Res<EnemyResource>,
-        time: Res<Time>,
+impl EnemyPlugin {
+    fn start_first_wave(
+        mut commands: Commands,
+        wave_spawner: WaveSpawner,
+        mut waves: ResMut<Waves>,
     ) {
-        for mut enemy_transform in enemy_query.iter_mut() {
-            enemy_transform.translation.x -= enemy_resource.speed * time.delta_seconds();
-        }
+        *waves = Waves {
+            wave: 1,
+            warp: None,
+        };
+
+        wave_spawner.spawn(&mut commands, waves.wave);
     }
 
-    fn wrap_enemy_around_window(
-        mut enemy_query: Query<&mut Transform, With<Enemy>>,
-        window_query: Query<&Window, With<PrimaryWindow>>,
+    /// Warps to the next wave once every enemy of the current one is gone.
+    fn advance_waves(
+        mut commands: Commands,
+        wave_spawner: WaveSpawner,
+        mut waves: ResMut<Waves>,
+        enemy_query: Query<(), With<Enemy>>,
+        time: Res<Time>,
     ) {
-        for mut enemy_transform in enemy_query.iter_mut() {
-            let window = window_query.single();
+        let Some(warp) = &mut waves.warp else {
+            if enemy_query.is_empty() {
+                waves.warp = Some(Timer::from_seconds(
+                    wave_spawner.enemy_resource.wave_delay,
+                    TimerMode::Once,
+                ));
+            }
+            return;
+        };
 
-            // Calculate the distance from the enemy to the edge of the window
-            let distance_to_edge = window.width() / 2. - enemy_transform.translation.x.abs();
+        if warp.tick(time.delta()).finished() {
+            waves.warp = None;
+            waves.wave += 1;
 
-            // If the enemy is outside the window, wrap them around to the other side
-            if distance_to_edge < 0. {
-                // Calculate the offset to move the player by to wrap them around to the other side of the window
-                let offset = -enemy_transform.translation.x.signum() * (window.width() / 2. - 1.);
-                enemy_transform.translation.x = offset;
-            }
+            wave_spawner.spawn(&mut commands, waves.wave);
         }
     }
 
-    fn check_bullet_collision(
-        mut commands: Commands,
-        mut enemy_query: Query<(Entity, &Transform), With<Enemy>>,
-        mut bullet_query: Query<(Entity, &Transform), With<Bullet>>,
-        bullet_resource: Res<BulletResource>,
-        asset_server: Res<AssetServer>,
-        audio: Res<Audio>,
+    fn fire_at_player(
+        mut gun_query: Query<(&Transform, &mut EnemyGun), With<Enemy>>,
+        time: Res<Time>,
+        mut ev_fire: EventWriter<FireProjectileEvent>,
     ) {
-        for (enemy, enemy_transform) in enemy_query.iter_mut() {
-            for (bullet, bullet_transform) in bullet_query.iter_mut() {
-                if bullet_transform
-                    .translation
-                    .distance(enemy_transform.translation)
-                    <= bullet_resource.radius
-                {
-                    let bullet_hit_sfx = asset_server.load("Audio/explosionCrunch_000.ogg");
-                    audio.play(bullet_hit_sfx);
-
-                    commands.entity(bullet).despawn_recursive();
-                    commands.entity(enemy).despawn_recursive();
-                }
+        for (transform, mut gun) in gun_query.iter_mut() {
+            if gun.0.tick(time.delta()).just_finished() {
+                ev_fire.send(FireProjectileEvent {
+                    kind: ProjectileKind::EnemyShot,
+                    owner: ProjectileOwner::Enemy,
+                    origin: *transform,
+                    modifiers: ProjectileModifiers::default(),
+                });
             }
         }
     }
 
-    fn check_bomb_collision(
+    #[allow(clippy::type_complexity)]
+    fn check_player_collision(
         mut commands: Commands,
-        mut enemy_query: Query<(Entity, &Transform), With<Enemy>>,
-        mut bomb_query: Query<(Entity, &Transform), With<Bomb>>,
-        bomb_resource: Res<BombResource>,
-        asset_server: Res<AssetServer>,
-        audio: Res<Audio>,
+        enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+        // An evading or freshly respawned player can't be hit
+        mut player_query: Query<
+            (&Transform, &mut VisualStates),
+            (With<Player>, Without<EvadeTimer>, Without<Invulnerable>),
+        >,
+        enemy_resource: Res<EnemyResource>,
+        mut player_resource: ResMut<PlayerResource>,
+        mut ev_sfx: EventWriter<PlaySfxEvent>,
+        mut ev_shake: EventWriter<CameraShakeEvent>,
     ) {
-        for (enemy, enemy_transform) in enemy_query.iter_mut() {
-            for (bomb, bomb_transform) in bomb_query.iter_mut() {
-                if bomb_transform
-                    .translation
-                    .distance(enemy_transform.translation)
-                    <= bomb_resource.radius
-                {
-                    let bomb_hit_sfx = asset_server.load("Audio/explosionCrunch_001.ogg");
-                    audio.play(bomb_hit_sfx);
-
-                    commands.entity(bomb).despawn_recursive();
-                    commands.entity(enemy).despawn_recursive();
-                }
+        let Ok((player_transform, mut visual_states)) = player_query.get_single_mut() else {
+            return;
+        };
+
+        for (enemy, enemy_transform) in enemy_query.iter() {
+            if enemy_transform
+                .translation
+                .distance(player_transform.translation)
+                <= enemy_resource.radius
+            {
+                ev_sfx.send(PlaySfxEvent(Sfx::BulletHit));
+                ev_shake.send(CameraShakeEvent(player_resource.damage_trauma));
+
+                player_resource.health -= enemy_resource.contact_damage;
+                visual_states.insert_for(VisualState::Damaged, 0.5);
+                commands.entity(enemy).despawn_recursive();
+
+                debug!("Health left: {}", player_resource.health);
             }
         }
     }
@@ -1,45 +1,174 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
 
-use crate::player::{Player, PlayerResource};
+use crate::{
+    player::{Movement, Player, PlayerResource},
+    sfx::{PlaySfxEvent, Sfx},
+    visual_state::{VisualState, VisualStates},
+    GameState,
+};
 
 pub struct EvadeEvent;
 
+/// Present on the player while evading, during which they can't be hit.
 #[derive(Component)]
 pub struct EvadeTimer {
     pub time: Timer,
+    /// The direction of the dash along the x axis: -1, 1, or 0 for no dash
+    pub dash_direction: f32,
+}
+
+#[derive(Resource)]
+pub struct EvadeResource {
+    /// How long an evade lasts, in seconds
+    pub duration: f32,
+    /// The distance per second the player dashes sideways at the start of an evade, if a
+    /// direction is held (0 disables the dash)
+    pub dash_speed: f32,
+    /// How long the dash lasts, in seconds
+    pub dash_duration: f32,
+    /// How long it takes to regain a single evade, in seconds
+    pub recharge_time: f32,
+    /// Evades don't recharge past this many
+    pub max_evades: u32,
 }
 
+#[derive(Resource)]
+struct EvadeRechargeTimer(Timer);
+
 pub struct EvadePlugin;
 impl Plugin for EvadePlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<EvadeEvent>()
-            .add_system(Self::start_player_evasion);
+        let evade_resource = EvadeResource {
+            duration: 1.,
+            dash_speed: 900.,
+            dash_duration: 0.15,
+            recharge_time: 5.,
+            max_evades: 3,
+        };
+
+        app.insert_resource(EvadeRechargeTimer(Timer::from_seconds(
+            evade_resource.recharge_time,
+            TimerMode::Repeating,
+        )))
+        .insert_resource(evade_resource)
+        .add_event::<EvadeEvent>()
+        .add_systems(
+            (
+                Self::start_player_evasion,
+                Self::dash,
+                Self::tick_evasion,
+                Self::recharge_evades,
+            )
+                .chain()
+                .in_set(OnUpdate(GameState::Playing)),
+        );
     }
 }
 
 impl EvadePlugin {
+    #[allow(clippy::type_complexity)]
     fn start_player_evasion(
         mut commands: Commands,
-        asset_server: Res<AssetServer>,
-        audio: Res<Audio>,
+        mut ev_sfx: EventWriter<PlaySfxEvent>,
         mut ev_evade: EventReader<EvadeEvent>,
-        player_query: Query<Entity, With<Player>>,
+        mut player_query: Query<
+            (
+                Entity,
+                &ActionState<Movement>,
+                &mut VisualStates,
+                Option<&EvadeTimer>,
+            ),
+            With<Player>,
+        >,
         mut player_resource: ResMut<PlayerResource>,
+        evade_resource: Res<EvadeResource>,
     ) {
         for _ev in ev_evade.iter() {
-            let evade_sfx = asset_server.load("Audio/forceField_000.ogg");
+            let (player_entity, movement_state, mut visual_states, evade_timer) =
+                player_query.single_mut();
 
-            let player_entity = player_query.single();
+            // Evading again mid-evade would only waste a charge
+            if evade_timer.is_some() {
+                continue;
+            }
 
             if player_resource.evades > 0 {
-                audio.play(evade_sfx);
+                ev_sfx.send(PlaySfxEvent(Sfx::Evade));
                 player_resource.evades -= 1;
+
+                let dash_direction = match (
+                    movement_state.pressed(Movement::Left),
+                    movement_state.pressed(Movement::Right),
+                ) {
+                    (true, false) => -1.,
+                    (false, true) => 1.,
+                    _ => 0.,
+                };
+
+                if dash_direction != 0. && evade_resource.dash_speed > 0. {
+                    ev_sfx.send(PlaySfxEvent(Sfx::Thruster));
+                }
+
+                visual_states.insert(VisualState::Evading);
                 commands.entity(player_entity).insert(EvadeTimer {
-                    time: Timer::from_seconds(1., TimerMode::Once),
+                    time: Timer::from_seconds(evade_resource.duration, TimerMode::Once),
+                    dash_direction,
                 });
             }
 
-            println!("Evades left: {}", player_resource.evades);
+            debug!("Evades left: {}", player_resource.evades);
+        }
+    }
+
+    fn tick_evasion(
+        mut commands: Commands,
+        mut player_query: Query<(Entity, &mut EvadeTimer, &mut VisualStates), With<Player>>,
+        time: Res<Time>,
+    ) {
+        for (player_entity, mut evade_timer, mut visual_states) in player_query.iter_mut() {
+            if evade_timer.time.tick(time.delta()).finished() {
+                visual_states.remove(VisualState::Evading);
+                commands.entity(player_entity).remove::<EvadeTimer>();
+            }
+        }
+    }
+
+    fn dash(
+        mut player_query: Query<(&mut Transform, &EvadeTimer), With<Player>>,
+        evade_resource: Res<EvadeResource>,
+        time: Res<Time>,
+    ) {
+        for (mut transform, evade_timer) in player_query.iter_mut() {
+            if evade_timer.time.elapsed_secs() < evade_resource.dash_duration {
+                transform.translation.x +=
+                    evade_timer.dash_direction * evade_resource.dash_speed * time.delta_seconds();
+            }
+        }
+    }
+
+    fn recharge_evades(
+        mut recharge_timer: ResMut<EvadeRechargeTimer>,
+        mut player_resource: ResMut<PlayerResource>,
+        evade_resource: Res<EvadeResource>,
+        time: Res<Time>,
+    ) {
+        if evade_resource.is_changed() {
+            recharge_timer
+                .0
+                .set_duration(Duration::from_secs_f32(evade_resource.recharge_time));
+        }
+
+        if player_resource.evades >= evade_resource.max_evades {
+            recharge_timer.0.reset();
+            return;
+        }
+
+        if recharge_timer.0.tick(time.delta()).just_finished() {
+            player_resource.evades += 1;
+            debug!("Evades left: {}", player_resource.evades);
         }
     }
 }
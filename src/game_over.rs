@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+
+use crate::{
+    assets::GameAssets,
+    despawn_all,
+    enemy::Waves,
+    menu::{MenuBuilder, MenuConfirmEvent, MenuStack, MenuSystem},
+    score::RunStats,
+    GameState,
+};
+
+#[derive(Component, Clone, Copy, Debug)]
+enum GameOverButton {
+    Retry,
+    MainMenu,
+}
+
+#[derive(Component)]
+struct GameOverMenu;
+
+/// Sums up the run in [`GameState::GameOver`], with the choice of another or going back to
+/// the title screen.
+pub struct GameOverScreenPlugin;
+impl Plugin for GameOverScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(Self::spawn_game_over_menu.in_schedule(OnEnter(GameState::GameOver)))
+            .add_system(despawn_all::<GameOverMenu>.in_schedule(OnExit(GameState::GameOver)))
+            .add_system(
+                Self::press_game_over_buttons
+                    .after(MenuSystem)
+                    .in_set(OnUpdate(GameState::GameOver)),
+            );
+    }
+}
+
+impl GameOverScreenPlugin {
+    fn spawn_game_over_menu(
+        mut commands: Commands,
+        mut menu_stack: ResMut<MenuStack>,
+        game_assets: Res<GameAssets>,
+        run_stats: Res<RunStats>,
+        waves: Res<Waves>,
+    ) {
+        let menu = MenuBuilder::new("Game over")
+            .with_line(format!("Score: {}", run_stats.score))
+            .with_line(format!("Wave reached: {}", waves.wave))
+            .with_line(format!("Accuracy: {:.0}%", run_stats.accuracy() * 100.))
+            .with_button("Retry", GameOverButton::Retry)
+            .with_button("Main menu", GameOverButton::MainMenu)
+            .spawn(&mut commands, &mut menu_stack, game_assets.font.clone());
+
+        commands.entity(menu).insert(GameOverMenu);
+    }
+
+    fn press_game_over_buttons(
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        button_query: Query<&GameOverButton>,
+        mut next_state: ResMut<NextState<GameState>>,
+    ) {
+        for ev in ev_confirm.iter() {
+            match button_query.get(ev.0) {
+                Ok(GameOverButton::Retry) => next_state.set(GameState::Playing),
+                Ok(GameOverButton::MainMenu) => next_state.set(GameState::MainMenu),
+                Err(_) => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use animation::AnimationPlugin;
+use assets::GameAssetsPlugin;
+use bevy::prelude::*;
+use boundary::BoundaryPlugin;
+use camera_effects::CameraShakeEvent;
+use difficulty::DifficultyPlugin;
+use enemy::EnemyPlugin;
+use evade::EvadePlugin;
+use lives::LivesPlugin;
+use movement::MovementPlugin;
+use music::MusicPlugin;
+use particles::ParticlePlugin;
+use pause::PausePlugin;
+use play_area::PlayArea;
+use player::PlayerPlugin;
+use power_up::PowerUpPlugin;
+use projectile::ProjectilePlugin;
+use score::ScorePlugin;
+use sfx::PlaySfxEvent;
+use sprites::SpriteRegistryPlugin;
+use visual_state::VisualStatePlugin;
+
+pub mod animation;
+pub mod assets;
+pub mod audio;
+pub mod boundary;
+pub mod camera_effects;
+pub mod difficulty;
+pub mod display;
+pub mod enemy;
+pub mod evade;
+pub mod game_over;
+pub mod lives;
+pub mod menu;
+pub mod movement;
+pub mod music;
+pub mod particles;
+pub mod pause;
+pub mod play_area;
+pub mod player;
+pub mod pool;
+pub mod power_up;
+pub mod projectile;
+pub mod score;
+pub mod settings;
+pub mod sfx;
+pub mod sim;
+pub mod sprites;
+pub mod starfield;
+pub mod title;
+pub mod visual_state;
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    /// Waiting on the [`assets::GameAssets`] to load
+    #[default]
+    Loading,
+    /// On the title screen
+    MainMenu,
+    Playing,
+    /// The player has run out of lives, and the run is being summed up
+    GameOver,
+}
+
+/// All of the game logic, without any windowing, rendering or audio output.
+///
+/// The binary pairs this with `DefaultPlugins`; [`sim::Simulation`] runs it under
+/// `MinimalPlugins` for tests.
+pub struct GameplayPlugin;
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<GameState>()
+            .init_resource::<PlayArea>()
+            .add_event::<PlaySfxEvent>()
+            .add_event::<CameraShakeEvent>()
+            .add_plugin(GameAssetsPlugin)
+            .add_plugin(PausePlugin)
+            .add_plugin(DifficultyPlugin)
+            .add_plugin(SpriteRegistryPlugin)
+            .add_plugin(VisualStatePlugin)
+            .add_plugin(MovementPlugin)
+            .add_plugin(BoundaryPlugin)
+            .add_plugin(PlayerPlugin)
+            .add_plugin(LivesPlugin)
+            .add_plugin(ProjectilePlugin)
+            .add_plugin(EnemyPlugin)
+            .add_plugin(EvadePlugin)
+            .add_plugin(PowerUpPlugin)
+            .add_plugin(ParticlePlugin)
+            .add_plugin(AnimationPlugin)
+            .add_plugin(ScorePlugin)
+            .add_plugin(MusicPlugin);
+    }
+}
+
+/// Despawns every entity with a `C`, for clearing up after a state.
+pub fn despawn_all<C: Component>(mut commands: Commands, query: Query<Entity, With<C>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
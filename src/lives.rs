@@ -0,0 +1,416 @@
+use bevy::prelude::*;
+
+use crate::{
+    assets::GameAssets,
+    despawn_all,
+    menu::{MenuBuilder, MenuButtonLabel, MenuConfirmEvent, MenuStack, MenuSystem},
+    movement::Velocity,
+    particles::{ParticleKind, SpawnParticlesEvent},
+    play_area::PlayArea,
+    player::{Player, PlayerResource, PLAYER_START},
+    score::RunStats,
+    sfx::{PlaySfxEvent, Sfx},
+    visual_state::{VisualState, VisualStates},
+    GameState,
+};
+
+#[derive(Resource)]
+pub struct LivesResource {
+    /// The spare ships a run, and each continue, starts with
+    pub starting_lives: u32,
+    /// An extra life is awarded every time the score passes a multiple of this (0 for none)
+    pub extra_life_every: u32,
+    /// How long the player is gone for after losing a life, in seconds
+    pub respawn_delay: f32,
+    /// How fast the player flies back in from the bottom of the play area
+    pub fly_in_speed: f32,
+    /// How long the player can't be hit once back in play, in seconds
+    pub invulnerability: f32,
+    /// How long the continue prompt waits before ending the run, in seconds
+    pub continue_time: f32,
+}
+
+impl Default for LivesResource {
+    fn default() -> Self {
+        Self {
+            starting_lives: 2,
+            extra_life_every: 5000,
+            respawn_delay: 1.5,
+            fly_in_speed: 150.,
+            invulnerability: 2.,
+            continue_time: 10.,
+        }
+    }
+}
+
+/// The lives left in the current run.
+#[derive(Resource, Debug, Default)]
+pub struct Lives {
+    /// Spare ships, not counting the one in play
+    pub remaining: u32,
+    /// How many extra lives the score has earned this run
+    pub extra_lives: u32,
+    pub continues_used: u32,
+    continue_countdown: Option<Timer>,
+}
+
+impl Lives {
+    /// Out of lives, and waiting on the player to continue or give up.
+    pub fn is_offering_continue(&self) -> bool {
+        self.continue_countdown.is_some()
+    }
+
+    /// The seconds left to continue in, while one is offered.
+    pub fn continue_time_left(&self) -> Option<f32> {
+        self.continue_countdown
+            .as_ref()
+            .map(|countdown| countdown.remaining_secs())
+    }
+}
+
+/// Present on the player from losing a life until they have flown back in, during which
+/// they're hidden at first and out of the player's control.
+#[derive(Component, Debug)]
+pub struct Respawn {
+    /// Until the player reappears
+    pub delay: Timer,
+}
+
+/// Present on the player while they can't be hit, only counting down once back in play.
+#[derive(Component, Debug)]
+pub struct Invulnerable(pub Timer);
+
+/// Takes up the continue offered when the player runs out of lives.
+pub struct ContinueEvent;
+
+/// Takes a life when the player runs out of health and brings them back, offering a
+/// continue once there are none left.
+pub struct LivesPlugin;
+impl Plugin for LivesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LivesResource>()
+            .init_resource::<Lives>()
+            .add_event::<ContinueEvent>()
+            .add_system(Self::reset_lives.in_schedule(OnEnter(GameState::Playing)))
+            .add_systems(
+                (
+                    Self::lose_lives,
+                    Self::respawn,
+                    Self::wear_off_invulnerability,
+                    Self::award_extra_lives,
+                    Self::take_continues,
+                    Self::count_down_continue,
+                )
+                    .in_set(OnUpdate(GameState::Playing)),
+            );
+    }
+}
+
+impl LivesPlugin {
+    fn reset_lives(mut lives: ResMut<Lives>, lives_resource: Res<LivesResource>) {
+        *lives = Lives {
+            remaining: lives_resource.starting_lives,
+            ..default()
+        };
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn lose_lives(
+        mut commands: Commands,
+        mut player_query: Query<
+            (Entity, &mut Transform, &mut Velocity, &mut Visibility),
+            (With<Player>, Without<Respawn>),
+        >,
+        player_resource: Res<PlayerResource>,
+        mut lives: ResMut<Lives>,
+        lives_resource: Res<LivesResource>,
+        play_area: Res<PlayArea>,
+        mut ev_particles: EventWriter<SpawnParticlesEvent>,
+    ) {
+        let Ok((player, mut transform, mut velocity, mut visibility)) =
+            player_query.get_single_mut()
+        else {
+            return;
+        };
+        if player_resource.health > 0. {
+            return;
+        }
+
+        ev_particles.send(SpawnParticlesEvent {
+            kind: ParticleKind::Explosion,
+            position: transform.translation.truncate(),
+        });
+
+        if lives.remaining > 0 {
+            lives.remaining -= 1;
+        } else {
+            lives.continue_countdown = Some(Timer::from_seconds(
+                lives_resource.continue_time,
+                TimerMode::Once,
+            ));
+        }
+
+        // Waits out of sight on the bottom edge, where it flies back in from
+        transform.translation = Vec3::new(
+            PLAYER_START.x,
+            -play_area.half_height(),
+            transform.translation.z,
+        );
+        velocity.0 = Vec2::ZERO;
+        *visibility = Visibility::Hidden;
+        commands.entity(player).insert((
+            Respawn {
+                delay: Timer::from_seconds(lives_resource.respawn_delay, TimerMode::Once),
+            },
+            Invulnerable(Timer::from_seconds(
+                lives_resource.invulnerability,
+                TimerMode::Once,
+            )),
+        ));
+
+        debug!("Lives left: {}", lives.remaining);
+    }
+
+    fn respawn(
+        mut commands: Commands,
+        mut player_query: Query<(
+            Entity,
+            &mut Respawn,
+            &mut Transform,
+            &mut Visibility,
+            &mut VisualStates,
+        )>,
+        mut player_resource: ResMut<PlayerResource>,
+        lives: Res<Lives>,
+        lives_resource: Res<LivesResource>,
+        time: Res<Time>,
+    ) {
+        // Nothing comes back until the player decides whether to continue
+        if lives.is_offering_continue() {
+            return;
+        }
+
+        for (player, mut respawn, mut transform, mut visibility, mut visual_states) in
+            player_query.iter_mut()
+        {
+            if !respawn.delay.finished() {
+                if respawn.delay.tick(time.delta()).just_finished() {
+                    player_resource.health = player_resource.max_health;
+                    *visibility = Visibility::Inherited;
+                    visual_states.insert(VisualState::Invulnerable);
+                }
+                continue;
+            }
+
+            transform.translation.y = (transform.translation.y
+                + lives_resource.fly_in_speed * time.delta_seconds())
+            .min(PLAYER_START.y);
+
+            if transform.translation.y >= PLAYER_START.y {
+                commands.entity(player).remove::<Respawn>();
+            }
+        }
+    }
+
+    fn wear_off_invulnerability(
+        mut commands: Commands,
+        mut invulnerable_query: Query<
+            (Entity, &mut Invulnerable, &mut VisualStates),
+            Without<Respawn>,
+        >,
+        time: Res<Time>,
+    ) {
+        for (entity, mut invulnerable, mut visual_states) in invulnerable_query.iter_mut() {
+            if invulnerable.0.tick(time.delta()).finished() {
+                visual_states.remove(VisualState::Invulnerable);
+                commands.entity(entity).remove::<Invulnerable>();
+            }
+        }
+    }
+
+    fn award_extra_lives(
+        run_stats: Res<RunStats>,
+        mut lives: ResMut<Lives>,
+        lives_resource: Res<LivesResource>,
+        mut ev_sfx: EventWriter<PlaySfxEvent>,
+    ) {
+        if lives_resource.extra_life_every == 0 {
+            return;
+        }
+
+        let earned = run_stats.score / lives_resource.extra_life_every;
+        while lives.extra_lives < earned {
+            lives.extra_lives += 1;
+            lives.remaining += 1;
+            ev_sfx.send(PlaySfxEvent(Sfx::PowerUp));
+
+            debug!("Lives left: {}", lives.remaining);
+        }
+    }
+
+    fn take_continues(
+        mut ev_continue: EventReader<ContinueEvent>,
+        mut lives: ResMut<Lives>,
+        lives_resource: Res<LivesResource>,
+    ) {
+        for _ev in ev_continue.iter() {
+            if lives.continue_countdown.take().is_some() {
+                lives.remaining = lives_resource.starting_lives;
+                lives.continues_used += 1;
+            }
+        }
+    }
+
+    fn count_down_continue(
+        mut lives: ResMut<Lives>,
+        time: Res<Time>,
+        mut next_state: ResMut<NextState<GameState>>,
+    ) {
+        let Some(countdown) = &mut lives.continue_countdown else {
+            return;
+        };
+
+        if countdown.tick(time.delta()).finished() {
+            next_state.set(GameState::GameOver);
+        }
+    }
+}
+
+#[derive(Component)]
+struct LivesDisplay;
+
+/// Shows the lives left in a corner of the screen during play.
+pub struct LivesDisplayPlugin;
+impl Plugin for LivesDisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(Self::spawn_lives_display.in_schedule(OnEnter(GameState::Playing)))
+            .add_system(Self::show_lives.in_set(OnUpdate(GameState::Playing)))
+            .add_system(despawn_all::<LivesDisplay>.in_schedule(OnExit(GameState::Playing)));
+    }
+}
+
+impl LivesDisplayPlugin {
+    fn spawn_lives_display(mut commands: Commands, game_assets: Res<GameAssets>) {
+        // The count is filled in once spawned
+        commands.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: game_assets.font.clone(),
+                    font_size: 18.,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(12.),
+                    bottom: Val::Px(12.),
+                    ..default()
+                },
+                ..default()
+            }),
+            LivesDisplay,
+        ));
+    }
+
+    fn show_lives(lives: Res<Lives>, mut text_query: Query<&mut Text, With<LivesDisplay>>) {
+        let label = format!("Lives: {}", lives.remaining);
+
+        for mut text in text_query.iter_mut() {
+            if text.sections[0].value != label {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+enum ContinueButton {
+    Continue,
+    GiveUp,
+}
+
+#[derive(Component)]
+struct ContinueMenu;
+
+/// Asks whether to continue while [`Lives`] offers one, counting down on the button.
+pub struct ContinueMenuPlugin;
+impl Plugin for ContinueMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                Self::toggle_continue_menu,
+                Self::label_countdown.after(Self::toggle_continue_menu),
+                Self::press_continue_buttons.after(MenuSystem),
+            )
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_system(despawn_all::<ContinueMenu>.in_schedule(OnExit(GameState::Playing)));
+    }
+}
+
+impl ContinueMenuPlugin {
+    fn toggle_continue_menu(
+        mut commands: Commands,
+        mut menu_stack: ResMut<MenuStack>,
+        game_assets: Res<GameAssets>,
+        lives: Res<Lives>,
+        run_stats: Res<RunStats>,
+        menu_query: Query<Entity, With<ContinueMenu>>,
+    ) {
+        match (lives.is_offering_continue(), menu_query.get_single()) {
+            (true, Err(_)) => {
+                // The countdown is filled in once spawned
+                let menu = MenuBuilder::new("Continue?")
+                    .with_line(format!("Score: {}", run_stats.score))
+                    .with_button("", ContinueButton::Continue)
+                    .with_button("Give up", ContinueButton::GiveUp)
+                    .spawn(&mut commands, &mut menu_stack, game_assets.font.clone());
+
+                commands.entity(menu).insert(ContinueMenu);
+            }
+            (false, Ok(menu)) => commands.entity(menu).despawn_recursive(),
+            _ => {}
+        }
+    }
+
+    fn label_countdown(
+        lives: Res<Lives>,
+        button_query: Query<(&ContinueButton, &Children)>,
+        mut label_query: Query<&mut Text, With<MenuButtonLabel>>,
+    ) {
+        let Some(time_left) = lives.continue_time_left() else {
+            return;
+        };
+        let label = format!("Continue ({:.0})", time_left.ceil());
+
+        for (button, children) in button_query.iter() {
+            if !matches!(button, ContinueButton::Continue) {
+                continue;
+            }
+
+            let mut labels = label_query.iter_many_mut(children);
+            while let Some(mut text) = labels.fetch_next() {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.clone();
+                }
+            }
+        }
+    }
+
+    fn press_continue_buttons(
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        button_query: Query<&ContinueButton>,
+        mut ev_continue: EventWriter<ContinueEvent>,
+        mut next_state: ResMut<NextState<GameState>>,
+    ) {
+        for ev in ev_confirm.iter() {
+            match button_query.get(ev.0) {
+                Ok(ContinueButton::Continue) => ev_continue.send(ContinueEvent),
+                Ok(ContinueButton::GiveUp) => next_state.set(GameState::GameOver),
+                Err(_) => {}
+            }
+        }
+    }
+}
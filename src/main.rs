@@ -1,17 +1,21 @@
 use bevy::log::LogPlugin;
 use bevy::prelude::*;
 use bevy_embedded_assets::EmbeddedAssetPlugin;
-use bomb::BombPlugin;
-use bullet::BulletPlugin;
-use enemy::EnemyPlugin;
-use evade::EvadePlugin;
-use player::PlayerPlugin;
-
-mod bomb;
-mod bullet;
-mod enemy;
-mod evade;
-mod player;
+use rustaga::{
+    assets::LoadingScreenPlugin,
+    audio::GameAudioPlugin,
+    camera_effects::CameraEffectsPlugin,
+    display::{DisplayPlugin, MainCamera},
+    game_over::GameOverScreenPlugin,
+    lives::{ContinueMenuPlugin, LivesDisplayPlugin},
+    menu::MenuPlugin,
+    pause::PauseMenuPlugin,
+    score::HighScorePlugin,
+    settings::SettingsMenuPlugin,
+    starfield::StarfieldPlugin,
+    title::TitleScreenPlugin,
+    GameplayPlugin,
+};
 
 fn main() {
     let mut app = App::new();
@@ -21,42 +25,39 @@ fn main() {
             .build()
             .add_before::<AssetPlugin, _>(EmbeddedAssetPlugin)
             .disable::<LogPlugin>()
+            .set(ImagePlugin::default_nearest())
             .set(WindowPlugin {
                 primary_window: Some(Window {
                     title: "Rustaga".into(),
-                    resolution: (500., 500.).into(),
+                    resolution: (500_f32, 500_f32).into(),
                     present_mode: bevy::window::PresentMode::AutoVsync,
                     fit_canvas_to_parent: true,
                     prevent_default_event_handling: false,
-                    resizable: false,
+                    resizable: true,
                     ..default()
                 }),
                 ..default()
             }),
     )
-    .add_plugin(PlayerPlugin)
-    .add_plugin(BulletPlugin)
-    .add_plugin(BombPlugin)
-    .add_plugin(EnemyPlugin)
-    .add_plugin(EvadePlugin)
-    .add_startup_system(spawn_basic_2d_camera)
-    .add_startup_system(play_background_music)
-    .add_system(bevy::window::close_on_esc);
+    .add_plugin(GameplayPlugin)
+    .add_plugin(GameAudioPlugin)
+    .add_plugin(DisplayPlugin)
+    .add_plugin(StarfieldPlugin)
+    .add_plugin(CameraEffectsPlugin)
+    .add_plugin(HighScorePlugin)
+    .add_plugin(LoadingScreenPlugin)
+    .add_plugin(MenuPlugin)
+    .add_plugin(TitleScreenPlugin)
+    .add_plugin(PauseMenuPlugin)
+    .add_plugin(SettingsMenuPlugin)
+    .add_plugin(ContinueMenuPlugin)
+    .add_plugin(LivesDisplayPlugin)
+    .add_plugin(GameOverScreenPlugin)
+    .add_startup_system(spawn_basic_2d_camera);
 
     app.run();
 }
 
 fn spawn_basic_2d_camera(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default());
-}
-
-fn play_background_music(asset_server: Res<AssetServer>, audio: Res<Audio>) {
-    let music_sfx = asset_server.load("Audio/Hero-Immortal.ogg");
-    audio.play_with_settings(
-        music_sfx,
-        PlaybackSettings {
-            repeat: true,
-            ..default()
-        },
-    );
+    commands.spawn((Camera2dBundle::default(), MainCamera));
 }
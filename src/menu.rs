@@ -0,0 +1,376 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+/// The keys that back out of menus, and open the pause menu during play.
+pub const BACK_KEYS: [KeyCode; 2] = [KeyCode::Escape, KeyCode::Back];
+
+/// Navigation through menus, the same on the keyboard and on a gamepad.
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum MenuAction {
+    Up,
+    Down,
+    /// Turns the selected setting down
+    Left,
+    /// Turns the selected setting up
+    Right,
+    Confirm,
+    /// Closes the menu on top, and opens the pause menu during play
+    Back,
+}
+
+/// A screen of buttons, navigated with [`MenuAction`]s or the mouse.
+///
+/// Only the menu opened last takes input, so menus can be opened on top of each other.
+#[derive(Component, Debug, Default)]
+pub struct Menu {
+    /// The index of the highlighted [`MenuButton`]
+    pub selected: usize,
+}
+
+#[derive(Component, Debug)]
+pub struct MenuButton {
+    pub menu: Entity,
+    /// The button's place in its menu, from the top
+    pub index: usize,
+}
+
+/// Marks the text of a [`MenuButton`], for menus that change it.
+#[derive(Component)]
+pub struct MenuButtonLabel;
+
+/// The open menus, from the first opened to the one on top.
+#[derive(Resource, Debug, Default)]
+pub struct MenuStack(Vec<Entity>);
+
+impl MenuStack {
+    /// The menu taking input, if any is open.
+    pub fn top(&self) -> Option<Entity> {
+        self.0.last().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Sent with a [`MenuButton`] when it is clicked, or confirmed while selected.
+pub struct MenuConfirmEvent(pub Entity);
+
+/// Sent with the selected [`MenuButton`] when Left (-1) or Right (1) is pressed.
+pub struct MenuAdjustEvent {
+    pub button: Entity,
+    pub step: i32,
+}
+
+/// Sent with the [`Menu`] on top when Back is pressed.
+pub struct MenuBackEvent(pub Entity);
+
+/// Runs before anything reading the menu events, so they are handled on the same frame.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MenuSystem;
+
+const BACKDROP_COLOR: Color = Color::rgba(0., 0., 0., 0.8);
+const LINE_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
+const BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.2);
+const SELECTED_BUTTON_COLOR: Color = Color::rgb(0.35, 0.35, 0.55);
+
+/// Lays out a [`Menu`] as a title and any lines of text over a column of buttons, each
+/// carrying a `B` saying what it does.
+pub struct MenuBuilder<B> {
+    title: String,
+    lines: Vec<String>,
+    buttons: Vec<(String, B)>,
+}
+
+impl<B: Component> MenuBuilder<B> {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            lines: Vec::new(),
+            buttons: Vec::new(),
+        }
+    }
+
+    /// Adds a line of text between the title and the buttons.
+    pub fn with_line(mut self, line: impl Into<String>) -> Self {
+        self.lines.push(line.into());
+        self
+    }
+
+    pub fn with_button(mut self, label: impl Into<String>, button: B) -> Self {
+        self.buttons.push((label.into(), button));
+        self
+    }
+
+    /// Spawns the menu on top of any already open.
+    pub fn spawn(
+        self,
+        commands: &mut Commands,
+        menu_stack: &mut MenuStack,
+        font: Handle<Font>,
+    ) -> Entity {
+        let menu = commands.spawn_empty().id();
+        menu_stack.0.push(menu);
+
+        commands
+            .entity(menu)
+            .insert((
+                Menu::default(),
+                NodeBundle {
+                    style: Style {
+                        size: Size::all(Val::Percent(100.)),
+                        position_type: PositionType::Absolute,
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        gap: Size::height(Val::Px(6.)),
+                        ..default()
+                    },
+                    background_color: BACKDROP_COLOR.into(),
+                    // Later menus cover earlier ones
+                    z_index: ZIndex::Global(menu_stack.0.len() as i32),
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn(
+                    TextBundle::from_section(
+                        self.title,
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 36.,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::bottom(Val::Px(16.)),
+                        ..default()
+                    }),
+                );
+
+                for line in self.lines {
+                    parent.spawn(TextBundle::from_section(
+                        line,
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 18.,
+                            color: LINE_COLOR,
+                        },
+                    ));
+                }
+
+                for (index, (label, button)) in self.buttons.into_iter().enumerate() {
+                    parent
+                        .spawn((
+                            MenuButton { menu, index },
+                            button,
+                            ButtonBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(300.), Val::Px(28.)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: BUTTON_COLOR.into(),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                MenuButtonLabel,
+                                TextBundle::from_section(
+                                    label,
+                                    TextStyle {
+                                        font: font.clone(),
+                                        font_size: 18.,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                            ));
+                        });
+                }
+            });
+
+        menu
+    }
+}
+
+/// Keyboard, mouse and gamepad navigation through [`Menu`]s, which other plugins spawn with
+/// a [`MenuBuilder`] and react to through the menu events.
+pub struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(InputManagerPlugin::<MenuAction>::default())
+            .init_resource::<MenuStack>()
+            .add_event::<MenuConfirmEvent>()
+            .add_event::<MenuAdjustEvent>()
+            .add_event::<MenuBackEvent>()
+            .add_startup_system(Self::spawn_menu_input)
+            .add_systems(
+                (
+                    Self::forget_closed_menus,
+                    Self::navigate_menus,
+                    Self::point_at_menus,
+                    Self::highlight_selected_buttons,
+                )
+                    .chain()
+                    .in_set(MenuSystem),
+            );
+    }
+}
+
+impl MenuPlugin {
+    fn spawn_menu_input(mut commands: Commands) {
+        commands.spawn(InputManagerBundle::<MenuAction> {
+            action_state: ActionState::default(),
+            input_map: InputMap::new([
+                (KeyCode::Up, MenuAction::Up),
+                (KeyCode::W, MenuAction::Up),
+                (KeyCode::Down, MenuAction::Down),
+                (KeyCode::S, MenuAction::Down),
+                (KeyCode::Left, MenuAction::Left),
+                (KeyCode::A, MenuAction::Left),
+                (KeyCode::Right, MenuAction::Right),
+                (KeyCode::D, MenuAction::Right),
+                (KeyCode::Return, MenuAction::Confirm),
+                (KeyCode::Space, MenuAction::Confirm),
+            ])
+            .insert_multiple(BACK_KEYS.map(|key| (key, MenuAction::Back)))
+            .insert_multiple([
+                (GamepadButtonType::DPadUp, MenuAction::Up),
+                (GamepadButtonType::DPadDown, MenuAction::Down),
+                (GamepadButtonType::DPadLeft, MenuAction::Left),
+                (GamepadButtonType::DPadRight, MenuAction::Right),
+                (GamepadButtonType::South, MenuAction::Confirm),
+                (GamepadButtonType::East, MenuAction::Back),
+                (GamepadButtonType::Start, MenuAction::Back),
+            ])
+            .insert_multiple([
+                (
+                    SingleAxis::positive_only(GamepadAxisType::LeftStickY, 0.5),
+                    MenuAction::Up,
+                ),
+                (
+                    SingleAxis::negative_only(GamepadAxisType::LeftStickY, -0.5),
+                    MenuAction::Down,
+                ),
+                (
+                    SingleAxis::negative_only(GamepadAxisType::LeftStickX, -0.5),
+                    MenuAction::Left,
+                ),
+                (
+                    SingleAxis::positive_only(GamepadAxisType::LeftStickX, 0.5),
+                    MenuAction::Right,
+                ),
+            ])
+            .build(),
+        });
+    }
+
+    fn forget_closed_menus(mut menu_stack: ResMut<MenuStack>, menu_query: Query<(), With<Menu>>) {
+        menu_stack.0.retain(|&menu| menu_query.contains(menu));
+    }
+
+    fn navigate_menus(
+        action_query: Query<&ActionState<MenuAction>>,
+        menu_stack: Res<MenuStack>,
+        mut menu_query: Query<&mut Menu>,
+        button_query: Query<(Entity, &MenuButton)>,
+        mut ev_confirm: EventWriter<MenuConfirmEvent>,
+        mut ev_adjust: EventWriter<MenuAdjustEvent>,
+        mut ev_back: EventWriter<MenuBackEvent>,
+    ) {
+        let Some(top) = menu_stack.top() else {
+            return;
+        };
+        let Ok(mut menu) = menu_query.get_mut(top) else {
+            return;
+        };
+
+        let buttons: Vec<_> = button_query
+            .iter()
+            .filter(|(_, button)| button.menu == top)
+            .collect();
+        let button_count = buttons.len();
+        let selected = buttons
+            .iter()
+            .find(|(_, button)| button.index == menu.selected)
+            .map(|&(entity, _)| entity);
+
+        for action_state in action_query.iter() {
+            if button_count > 0 {
+                // Wraps around at either end
+                if action_state.just_pressed(MenuAction::Up) {
+                    menu.selected = (menu.selected + button_count - 1) % button_count;
+                }
+                if action_state.just_pressed(MenuAction::Down) {
+                    menu.selected = (menu.selected + 1) % button_count;
+                }
+            }
+
+            if let Some(selected) = selected {
+                if action_state.just_pressed(MenuAction::Left) {
+                    ev_adjust.send(MenuAdjustEvent {
+                        button: selected,
+                        step: -1,
+                    });
+                }
+                if action_state.just_pressed(MenuAction::Right) {
+                    ev_adjust.send(MenuAdjustEvent {
+                        button: selected,
+                        step: 1,
+                    });
+                }
+                if action_state.just_pressed(MenuAction::Confirm) {
+                    ev_confirm.send(MenuConfirmEvent(selected));
+                }
+            }
+
+            if action_state.just_pressed(MenuAction::Back) {
+                ev_back.send(MenuBackEvent(top));
+            }
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn point_at_menus(
+        menu_stack: Res<MenuStack>,
+        mut menu_query: Query<&mut Menu>,
+        button_query: Query<(Entity, &MenuButton, &Interaction), Changed<Interaction>>,
+        mut ev_confirm: EventWriter<MenuConfirmEvent>,
+    ) {
+        for (entity, button, interaction) in button_query.iter() {
+            // Menus underneath the top one are covered up
+            if menu_stack.top() != Some(button.menu) {
+                continue;
+            }
+
+            match interaction {
+                Interaction::Hovered => {}
+                Interaction::Clicked => ev_confirm.send(MenuConfirmEvent(entity)),
+                Interaction::None => continue,
+            }
+
+            if let Ok(mut menu) = menu_query.get_mut(button.menu) {
+                menu.selected = button.index;
+            }
+        }
+    }
+
+    fn highlight_selected_buttons(
+        menu_query: Query<&Menu, Changed<Menu>>,
+        mut button_query: Query<(&MenuButton, &mut BackgroundColor)>,
+    ) {
+        for (button, mut background_color) in button_query.iter_mut() {
+            let Ok(menu) = menu_query.get(button.menu) else {
+                continue;
+            };
+
+            *background_color = if button.index == menu.selected {
+                SELECTED_BUTTON_COLOR.into()
+            } else {
+                BUTTON_COLOR.into()
+            };
+        }
+    }
+}
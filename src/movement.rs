@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+
+/// The distance an entity travels per second along each axis.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Deref, DerefMut)]
+pub struct Velocity(pub Vec2);
+
+/// Systems that move entities by their [`Velocity`]; anything that steers an entity should
+/// run before it.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MovementSystem;
+
+pub struct MovementPlugin;
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        app.configure_set(MovementSystem.in_set(OnUpdate(GameState::Playing)))
+            .add_system(Self::apply_velocity.in_set(MovementSystem));
+    }
+}
+
+impl MovementPlugin {
+    fn apply_velocity(mut query: Query<(&mut Transform, &Velocity)>, time: Res<Time>) {
+        for (mut transform, velocity) in query.iter_mut() {
+            transform.translation += velocity.extend(0.) * time.delta_seconds();
+        }
+    }
+}
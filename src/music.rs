@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::{enemy::Enemy, GameState};
+
+/// The piece of music that fits what's going on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MusicTrack {
+    #[default]
+    Menu,
+    Gameplay,
+    Boss,
+    GameOver,
+}
+
+impl MusicTrack {
+    pub const ALL: [MusicTrack; 4] = [
+        MusicTrack::Menu,
+        MusicTrack::Gameplay,
+        MusicTrack::Boss,
+        MusicTrack::GameOver,
+    ];
+
+    pub fn path(&self) -> &'static str {
+        // Only one piece of music ships so far, so every track shares it
+        match self {
+            MusicTrack::Menu | MusicTrack::Gameplay | MusicTrack::Boss | MusicTrack::GameOver => {
+                "Audio/Hero-Immortal.ogg"
+            }
+        }
+    }
+
+    /// The stem mixed in under the track as the [`MusicCue::intensity`] rises, if it has one.
+    /// Tracks without one quicken instead, by [`MusicResource::speed`]
+    pub fn layer_path(&self) -> Option<&'static str> {
+        // No stems of the music ship yet
+        match self {
+            MusicTrack::Menu | MusicTrack::Gameplay | MusicTrack::Boss | MusicTrack::GameOver => {
+                None
+            }
+        }
+    }
+}
+
+/// What the music should be playing, worked out from the game so that the audio output
+/// only has to follow it.
+#[derive(Resource, Debug, Default)]
+pub struct MusicCue {
+    pub track: MusicTrack,
+    /// How hectic things are, from 0 to 1
+    pub intensity: f32,
+    /// Set by a boss fight while it's on, counting phases from 0. There are no boss fights
+    /// yet, so nothing sets it and the boss track doesn't play until one does
+    pub boss_phase: Option<u32>,
+}
+
+#[derive(Resource)]
+pub struct MusicResource {
+    /// How many enemies on screen at once play the music at full intensity
+    pub enemies_for_full_intensity: usize,
+    /// How many phases a boss fight has, the last of them playing at full intensity
+    pub boss_phases: u32,
+    /// How long one track takes to fade into the next, in seconds
+    pub crossfade: f32,
+    /// How much faster than normal a track without a layer plays at full intensity, as a
+    /// fraction of its normal speed
+    pub max_speedup: f32,
+}
+
+impl MusicResource {
+    /// How quickly a track without a layer plays at `intensity`, 1 being its normal speed.
+    pub fn speed(&self, intensity: f32) -> f32 {
+        1. + self.max_speedup * intensity.clamp(0., 1.)
+    }
+}
+
+impl Default for MusicResource {
+    fn default() -> Self {
+        Self {
+            enemies_for_full_intensity: 10,
+            boss_phases: 3,
+            crossfade: 2.,
+            max_speedup: 0.15,
+        }
+    }
+}
+
+pub struct MusicPlugin;
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicResource>()
+            .init_resource::<MusicCue>()
+            .add_system(Self::update_music_cue);
+    }
+}
+
+impl MusicPlugin {
+    fn update_music_cue(
+        state: Res<State<GameState>>,
+        music_resource: Res<MusicResource>,
+        mut music_cue: ResMut<MusicCue>,
+        enemy_query: Query<(), With<Enemy>>,
+    ) {
+        let (track, intensity) = match (state.0, music_cue.boss_phase) {
+            (GameState::Loading | GameState::MainMenu, _) => (MusicTrack::Menu, 0.),
+            (GameState::GameOver, _) => (MusicTrack::GameOver, 0.),
+            (GameState::Playing, Some(phase)) => (
+                MusicTrack::Boss,
+                (phase + 1) as f32 / music_resource.boss_phases.max(1) as f32,
+            ),
+            (GameState::Playing, None) => (
+                MusicTrack::Gameplay,
+                enemy_query.iter().count() as f32
+                    / music_resource.enemies_for_full_intensity.max(1) as f32,
+            ),
+        };
+
+        music_cue.track = track;
+        music_cue.intensity = intensity.clamp(0., 1.);
+    }
+}
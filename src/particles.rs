@@ -0,0 +1,337 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    despawn_all,
+    enemy::EnemyDestroyedEvent,
+    movement::Velocity,
+    pool::{EntityPool, Pooled},
+    GameState,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParticleKind {
+    Explosion,
+    BulletImpact,
+    BombBlast,
+    EngineTrail,
+}
+
+/// How the particles of one [`ParticleKind`] are spawned and change over their life.
+#[derive(Clone, Debug)]
+pub struct ParticleEffect {
+    /// How many particles a [`SpawnParticlesEvent`] spawns at once
+    pub burst: usize,
+    /// How many bursts a [`ParticleEmitter`] spawns per second
+    pub rate: f32,
+    /// How long each particle lasts, in seconds
+    pub lifetime: f32,
+    /// The range each particle's speed is picked from
+    pub speed: (f32, f32),
+    /// The direction particles fly off in
+    pub direction: Vec2,
+    /// How far either side of `direction` particles can fly off, in radians (π spreads
+    /// them all around)
+    pub spread: f32,
+    /// The color at the start and the end of a particle's life
+    pub color: (Color, Color),
+    /// The width at the start and the end of a particle's life
+    pub size: (f32, f32),
+}
+
+#[derive(Resource)]
+pub struct ParticleResource {
+    pub effects: HashMap<ParticleKind, ParticleEffect>,
+    /// No more particles than this are alive at once; bursts past it are cut short
+    pub max_particles: usize,
+}
+
+impl Default for ParticleResource {
+    fn default() -> Self {
+        Self {
+            effects: HashMap::from([
+                (
+                    ParticleKind::Explosion,
+                    ParticleEffect {
+                        burst: 24,
+                        rate: 0.,
+                        lifetime: 0.6,
+                        speed: (60., 180.),
+                        direction: Vec2::Y,
+                        spread: PI,
+                        color: (Color::YELLOW, Color::rgba(1., 0.2, 0., 0.)),
+                        size: (8., 2.),
+                    },
+                ),
+                (
+                    ParticleKind::BulletImpact,
+                    ParticleEffect {
+                        burst: 6,
+                        rate: 0.,
+                        lifetime: 0.25,
+                        speed: (40., 120.),
+                        direction: Vec2::Y,
+                        spread: PI,
+                        color: (Color::WHITE, Color::rgba(1., 1., 0., 0.)),
+                        size: (4., 1.),
+                    },
+                ),
+                (
+                    ParticleKind::BombBlast,
+                    ParticleEffect {
+                        burst: 48,
+                        rate: 0.,
+                        lifetime: 0.8,
+                        speed: (100., 300.),
+                        direction: Vec2::Y,
+                        spread: PI,
+                        color: (Color::ORANGE, Color::rgba(0.5, 0., 0., 0.)),
+                        size: (12., 3.),
+                    },
+                ),
+                (
+                    ParticleKind::EngineTrail,
+                    ParticleEffect {
+                        burst: 1,
+                        rate: 60.,
+                        lifetime: 0.4,
+                        speed: (40., 80.),
+                        direction: Vec2::NEG_Y,
+                        spread: 0.3,
+                        color: (Color::ORANGE, Color::rgba(1., 0., 0., 0.)),
+                        size: (5., 1.),
+                    },
+                ),
+            ]),
+            max_particles: 2000,
+        }
+    }
+}
+
+impl ParticleResource {
+    pub fn effect(&self, kind: ParticleKind) -> &ParticleEffect {
+        &self.effects[&kind]
+    }
+}
+
+/// Spawns a burst of particles of `kind` at `position`.
+pub struct SpawnParticlesEvent {
+    pub kind: ParticleKind,
+    pub position: Vec2,
+}
+
+#[derive(Component, Debug)]
+pub struct Particle {
+    pub kind: ParticleKind,
+    /// How long the particle has been alive, in seconds
+    pub age: f32,
+}
+
+/// Keeps spawning bursts of particles at the entity's position, at the effect's rate.
+#[derive(Component, Debug)]
+pub struct ParticleEmitter {
+    pub kind: ParticleKind,
+    /// Where the particles appear relative to the entity
+    pub offset: Vec2,
+    /// Time towards the next burst, in bursts
+    progress: f32,
+}
+
+impl ParticleEmitter {
+    pub fn new(kind: ParticleKind, offset: Vec2) -> Self {
+        Self {
+            kind,
+            offset,
+            progress: 0.,
+        }
+    }
+}
+
+#[derive(Bundle)]
+struct ParticleBundle {
+    particle: Particle,
+    #[bundle]
+    sprite: SpriteBundle,
+    velocity: Velocity,
+    pooled: Pooled,
+}
+
+/// Lightweight sprite particles for explosions, impacts and trails.
+pub struct ParticlePlugin;
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ParticleResource>()
+            .init_resource::<EntityPool<ParticleKind>>()
+            .add_event::<SpawnParticlesEvent>()
+            .add_systems(
+                (
+                    Self::explode_destroyed_enemies,
+                    Self::emit_particles,
+                    Self::spawn_particles
+                        .after(Self::explode_destroyed_enemies)
+                        .after(Self::emit_particles),
+                    Self::age_particles,
+                )
+                    .in_set(OnUpdate(GameState::Playing)),
+            )
+            .add_system(Self::recycle_particles.in_base_set(CoreSet::PostUpdate))
+            .add_system(despawn_all::<Particle>.in_schedule(OnExit(GameState::Playing)));
+    }
+}
+
+impl ParticlePlugin {
+    fn explode_destroyed_enemies(
+        mut ev_enemy_destroyed: EventReader<EnemyDestroyedEvent>,
+        mut ev_particles: EventWriter<SpawnParticlesEvent>,
+    ) {
+        for ev in ev_enemy_destroyed.iter() {
+            ev_particles.send(SpawnParticlesEvent {
+                kind: ParticleKind::Explosion,
+                position: ev.0,
+            });
+        }
+    }
+
+    fn emit_particles(
+        mut emitter_query: Query<(&mut ParticleEmitter, &Transform, Option<&Visibility>)>,
+        particle_resource: Res<ParticleResource>,
+        time: Res<Time>,
+        mut ev_particles: EventWriter<SpawnParticlesEvent>,
+    ) {
+        for (mut emitter, transform, visibility) in emitter_query.iter_mut() {
+            // Nothing trails from an entity that isn't there to see
+            if visibility == Some(&Visibility::Hidden) {
+                continue;
+            }
+
+            emitter.progress += particle_resource.effect(emitter.kind).rate * time.delta_seconds();
+
+            while emitter.progress >= 1. {
+                emitter.progress -= 1.;
+                ev_particles.send(SpawnParticlesEvent {
+                    kind: emitter.kind,
+                    position: (*transform * emitter.offset.extend(0.)).truncate(),
+                });
+            }
+        }
+    }
+
+    fn spawn_particles(
+        mut commands: Commands,
+        particle_query: Query<&Pooled, With<Particle>>,
+        particle_resource: Res<ParticleResource>,
+        mut particle_pool: ResMut<EntityPool<ParticleKind>>,
+        mut ev_particles: EventReader<SpawnParticlesEvent>,
+    ) {
+        let mut alive = particle_query
+            .iter()
+            .filter(|pooled| pooled.is_active())
+            .count();
+
+        for ev in ev_particles.iter() {
+            let effect = particle_resource.effect(ev.kind);
+            let burst = effect
+                .burst
+                .min(particle_resource.max_particles.saturating_sub(alive));
+            alive += burst;
+
+            for _ in 0..burst {
+                let angle = (fastrand::f32() * 2. - 1.) * effect.spread;
+                let speed = effect.speed.0 + fastrand::f32() * (effect.speed.1 - effect.speed.0);
+
+                let bundle = ParticleBundle {
+                    particle: Particle {
+                        kind: ev.kind,
+                        age: 0.,
+                    },
+                    sprite: SpriteBundle {
+                        sprite: Sprite {
+                            color: effect.color.0,
+                            custom_size: Some(Vec2::splat(effect.size.0)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(ev.position.extend(0.)),
+                        ..default()
+                    },
+                    velocity: Velocity(
+                        Vec2::from_angle(angle).rotate(effect.direction.normalize_or_zero())
+                            * speed,
+                    ),
+                    pooled: Pooled::active(),
+                };
+
+                let parked = std::iter::from_fn(|| particle_pool.take(ev.kind))
+                    .find(|&entity| commands.get_entity(entity).is_some());
+                match parked {
+                    Some(entity) => {
+                        commands.entity(entity).insert(bundle);
+                    }
+                    None => {
+                        commands.spawn(bundle);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fades and shrinks particles over their life, releasing them at the end of it.
+    fn age_particles(
+        mut particle_query: Query<(&mut Particle, &mut Sprite, &mut Pooled)>,
+        particle_resource: Res<ParticleResource>,
+        time: Res<Time>,
+    ) {
+        for (mut particle, mut sprite, mut pooled) in particle_query.iter_mut() {
+            if !pooled.is_active() {
+                continue;
+            }
+
+            let effect = particle_resource.effect(particle.kind);
+            particle.age += time.delta_seconds();
+
+            if particle.age >= effect.lifetime {
+                pooled.release();
+                continue;
+            }
+
+            let t = particle.age / effect.lifetime;
+            let (start_color, end_color) = effect.color;
+            sprite.color = (Vec4::from(start_color.as_rgba_f32())
+                .lerp(Vec4::from(end_color.as_rgba_f32()), t))
+            .into();
+            sprite.custom_size = Some(Vec2::splat(
+                effect.size.0 + (effect.size.1 - effect.size.0) * t,
+            ));
+        }
+    }
+
+    /// Parks released particles out of the way.
+    #[allow(clippy::type_complexity)]
+    fn recycle_particles(
+        mut query: Query<
+            (
+                Entity,
+                &Particle,
+                &Pooled,
+                &mut Transform,
+                &mut Velocity,
+                &mut Visibility,
+            ),
+            Changed<Pooled>,
+        >,
+        mut particle_pool: ResMut<EntityPool<ParticleKind>>,
+    ) {
+        for (entity, particle, pooled, mut transform, mut velocity, mut visibility) in
+            query.iter_mut()
+        {
+            if pooled.is_active() {
+                continue;
+            }
+
+            *transform = Transform::IDENTITY;
+            velocity.0 = Vec2::ZERO;
+            *visibility = Visibility::Hidden;
+            particle_pool.park(particle.kind, entity);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::{
+    assets::GameAssets,
+    despawn_all,
+    menu::{MenuAction, MenuBackEvent, MenuBuilder, MenuConfirmEvent, MenuStack, MenuSystem},
+    settings::open_settings_menu,
+    GameState,
+};
+
+/// Whether gameplay is running, only meaningful during [`GameState::Playing`].
+///
+/// A separate state rather than a [`GameState`], so pausing doesn't leave
+/// [`GameState::Playing`] and tear the run down.
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+/// Freezes gameplay and [`Time`] while [`PauseState::Paused`].
+pub struct PausePlugin;
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<PauseState>()
+            // Stops every system that only runs while playing
+            .configure_set(OnUpdate(GameState::Playing).run_if(in_state(PauseState::Running)))
+            .add_system(Self::pause_time.in_schedule(OnEnter(PauseState::Paused)))
+            .add_system(Self::resume_time.in_schedule(OnExit(PauseState::Paused)))
+            .add_system(Self::resume.in_schedule(OnExit(GameState::Playing)));
+    }
+}
+
+impl PausePlugin {
+    fn pause_time(mut time: ResMut<Time>) {
+        time.pause();
+    }
+
+    fn resume_time(mut time: ResMut<Time>) {
+        time.unpause();
+    }
+
+    /// Leaving the run by any route unpauses, so the next one doesn't start frozen.
+    fn resume(mut next_pause_state: ResMut<NextState<PauseState>>) {
+        next_pause_state.set(PauseState::Running);
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+enum PauseButton {
+    Resume,
+    Settings,
+    Restart,
+    QuitToMenu,
+}
+
+#[derive(Component)]
+struct PauseMenu;
+
+/// Opens a pause menu on Back during play.
+pub struct PauseMenuPlugin;
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            Self::pause_on_back
+                .after(MenuSystem)
+                .in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_system(Self::spawn_pause_menu.in_schedule(OnEnter(PauseState::Paused)))
+        .add_system(despawn_all::<PauseMenu>.in_schedule(OnExit(PauseState::Paused)))
+        .add_systems(
+            (Self::press_pause_buttons, Self::open_settings)
+                .after(MenuSystem)
+                .distributive_run_if(in_state(PauseState::Paused)),
+        );
+    }
+}
+
+impl PauseMenuPlugin {
+    fn pause_on_back(
+        action_query: Query<&ActionState<MenuAction>>,
+        menu_stack: Res<MenuStack>,
+        mut next_pause_state: ResMut<NextState<PauseState>>,
+    ) {
+        if menu_stack.is_empty()
+            && action_query
+                .iter()
+                .any(|action_state| action_state.just_pressed(MenuAction::Back))
+        {
+            next_pause_state.set(PauseState::Paused);
+        }
+    }
+
+    fn spawn_pause_menu(
+        mut commands: Commands,
+        mut menu_stack: ResMut<MenuStack>,
+        game_assets: Res<GameAssets>,
+    ) {
+        let menu = MenuBuilder::new("Paused")
+            .with_button("Resume", PauseButton::Resume)
+            .with_button("Settings", PauseButton::Settings)
+            .with_button("Restart", PauseButton::Restart)
+            .with_button("Quit to menu", PauseButton::QuitToMenu)
+            .spawn(&mut commands, &mut menu_stack, game_assets.font.clone());
+
+        commands.entity(menu).insert(PauseMenu);
+    }
+
+    fn press_pause_buttons(
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        mut ev_back: EventReader<MenuBackEvent>,
+        button_query: Query<&PauseButton>,
+        pause_menu_query: Query<(), With<PauseMenu>>,
+        mut next_game_state: ResMut<NextState<GameState>>,
+        mut next_pause_state: ResMut<NextState<PauseState>>,
+    ) {
+        let backed_out = ev_back
+            .iter()
+            .any(|ev| pause_menu_query.contains(ev.0))
+            .then_some(PauseButton::Resume);
+        let pressed = ev_confirm
+            .iter()
+            .filter_map(|ev| button_query.get(ev.0).ok().copied());
+
+        for button in pressed.chain(backed_out) {
+            match button {
+                PauseButton::Resume => next_pause_state.set(PauseState::Running),
+                PauseButton::Settings => {}
+                // Entering the state again starts a fresh run
+                PauseButton::Restart => next_game_state.set(GameState::Playing),
+                PauseButton::QuitToMenu => next_game_state.set(GameState::MainMenu),
+            }
+        }
+    }
+
+    fn open_settings(
+        mut commands: Commands,
+        mut menu_stack: ResMut<MenuStack>,
+        game_assets: Res<GameAssets>,
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        button_query: Query<&PauseButton>,
+        pause_menu_query: Query<Entity, With<PauseMenu>>,
+    ) {
+        for ev in ev_confirm.iter() {
+            if let (Ok(PauseButton::Settings), Ok(pause_menu)) =
+                (button_query.get(ev.0), pause_menu_query.get_single())
+            {
+                let settings_menu =
+                    open_settings_menu(&mut commands, &mut menu_stack, game_assets.font.clone());
+
+                // Closes along with the pause menu
+                commands.entity(pause_menu).add_child(settings_menu);
+            }
+        }
+    }
+}
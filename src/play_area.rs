@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+
+/// The logical bounds of the arena, centered on the origin.
+///
+/// Gameplay systems use this instead of the window size, so the arena stays the same no
+/// matter how the window is scaled, and the simulation can run without a window at all.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct PlayArea {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for PlayArea {
+    fn default() -> Self {
+        Self {
+            width: 500.,
+            height: 500.,
+        }
+    }
+}
+
+impl PlayArea {
+    pub fn half_width(&self) -> f32 {
+        self.width / 2.
+    }
+
+    pub fn half_height(&self) -> f32 {
+        self.height / 2.
+    }
+}
@@ -1,23 +1,37 @@
-use bevy::{prelude::*, utils::HashMap, window::PrimaryWindow};
+use bevy::{prelude::*, utils::HashMap};
 use leafwing_input_manager::{plugin::InputManagerSystem, prelude::*};
 
 use crate::{
-    bomb::ShootBombEvent,
-    bullet::ShootBulletEvent,
-    evade::{EvadeEvent, EvadeTimer},
+    animation::Banking,
+    boundary::BoundaryBehavior,
+    despawn_all,
+    difficulty::{DifficultyScaling, DifficultySystem},
+    evade::EvadeEvent,
+    lives::Respawn,
+    menu::BACK_KEYS,
+    movement::{MovementSystem, Velocity},
+    particles::{ParticleEmitter, ParticleKind},
+    play_area::PlayArea,
+    projectile::{FireProjectileEvent, ProjectileKind, ProjectileModifiers, ProjectileOwner},
+    sfx::{PlaySfxEvent, Sfx},
+    sprites::{SpriteName, SpriteRegistry},
+    visual_state::{Appearance, VisualState, VisualStates},
+    GameState,
 };
 
 #[derive(Component)]
 pub struct Player;
 
 #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
-enum Movement {
+pub enum Movement {
     Left,
     Right,
+    Up,
+    Down,
 }
 
 #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
-enum Slot {
+pub enum Slot {
     Primary,
     Secondary,
     Ability1,
@@ -27,10 +41,11 @@ enum Slot {
 }
 
 #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
-enum Ability {
+pub enum Ability {
     Evade,
     Bomb,
     ShootBullet,
+    HomingMissile,
 }
 
 #[derive(Component, Debug, Default, Deref, DerefMut)]
@@ -38,19 +53,160 @@ struct AbilitySlotMap {
     map: HashMap<Slot, Ability>,
 }
 
+/// How the player's velocity responds to movement input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MovementModel {
+    /// Move at full speed while a direction is held and stop dead when released
+    Instant,
+    /// Speed up while a direction is held and coast to a stop when released
+    Inertial {
+        /// How quickly the velocity changes towards the held direction, per second
+        acceleration: f32,
+        /// How quickly the player slows down with no direction held, per second
+        friction: f32,
+    },
+}
+
+/// How the player is steering, which drives the sound of their engine.
+#[derive(Component, Debug, Default)]
+pub struct Thrust {
+    /// The direction movement input points in, zero when none is held
+    pub direction: Vec2,
+    /// The player's speed as a fraction of their top speed
+    pub throttle: f32,
+}
+
 #[derive(Resource)]
 pub struct PlayerResource {
+    /// The maximum distance the player travels per second
     pub movement_speed: f32,
+    pub movement_model: MovementModel,
+    /// Lets the player move up and down within this fraction of the play area, measured
+    /// from the bottom (`None` keeps them on a fixed row)
+    pub vertical_band: Option<f32>,
+    /// What happens when the player reaches the edge of the play area
+    pub boundary_behavior: BoundaryBehavior,
+    /// What's left of the player's health, which costs a life once it runs out
     pub health: f32,
+    /// The health each run starts with
+    pub max_health: f32,
+    /// How hard the camera shakes when the player takes damage, from 0 to 1
+    pub damage_trauma: f32,
     pub evades: u32,
     pub bombs: u32,
+    pub missiles: u32,
+    /// Added to every projectile the player fires, built up by collecting power-ups
+    pub projectile_modifiers: ProjectileModifiers,
+}
+
+impl Default for PlayerResource {
+    fn default() -> Self {
+        Self {
+            movement_speed: 250.,
+            movement_model: MovementModel::Instant,
+            vertical_band: None,
+            boundary_behavior: BoundaryBehavior::Wrap,
+            health: 100.,
+            max_health: 100.,
+            damage_trauma: 0.5,
+            evades: 3,
+            bombs: 3,
+            missiles: 5,
+            projectile_modifiers: ProjectileModifiers::default(),
+        }
+    }
+}
+
+impl PlayerResource {
+    /// Puts back the health, ammo and power-ups a run starts with, keeping the rest of the
+    /// tuning.
+    pub fn reset_run(&mut self) {
+        let start = Self::default();
+
+        self.health = self.max_health;
+        self.evades = start.evades;
+        self.bombs = start.bombs;
+        self.missiles = start.missiles;
+        self.projectile_modifiers = start.projectile_modifiers;
+    }
+}
+
+/// The key bound to each [`Slot`], which can be changed from the settings menu.
+///
+/// The mouse buttons always fire the primary and secondary slots as well.
+#[derive(Resource, Clone, Debug)]
+pub struct SlotBindings(pub HashMap<Slot, KeyCode>);
+
+impl Default for SlotBindings {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (Slot::Primary, KeyCode::Z),
+            (Slot::Secondary, KeyCode::X),
+            (Slot::Ability1, KeyCode::Space),
+            (Slot::Ability2, KeyCode::Q),
+            (Slot::Ability3, KeyCode::W),
+            (Slot::Ability4, KeyCode::E),
+        ]))
+    }
+}
+
+impl SlotBindings {
+    pub fn key(&self, slot: Slot) -> Option<KeyCode> {
+        self.0.get(&slot).copied()
+    }
+
+    /// Whether `key` moves the player or pauses, and so can't be bound to a slot.
+    pub fn is_reserved(key: KeyCode) -> bool {
+        MOVEMENT_KEYS
+            .iter()
+            .any(|&(movement_key, _)| movement_key == key)
+            || BACK_KEYS.contains(&key)
+    }
+
+    /// Binds `key` to `slot`, handing the slot's old key to whichever slot had `key`, so no
+    /// key ever fires two slots. Returns false, changing nothing, for a reserved key.
+    pub fn bind(&mut self, slot: Slot, key: KeyCode) -> bool {
+        if Self::is_reserved(key) {
+            return false;
+        }
+
+        let old_key = self.0.insert(slot, key);
+        let clashing_slot = self
+            .0
+            .iter()
+            .find(|&(&other_slot, &other_key)| other_slot != slot && other_key == key)
+            .map(|(&other_slot, _)| other_slot);
+
+        if let Some(clashing_slot) = clashing_slot {
+            match old_key {
+                Some(old_key) => self.0.insert(clashing_slot, old_key),
+                None => self.0.remove(&clashing_slot),
+            };
+        }
+
+        true
+    }
+
+    pub fn input_map(&self) -> InputMap<Slot> {
+        let mut input_map = InputMap::new(self.0.iter().map(|(&slot, &key)| (key, slot)));
+        input_map
+            .insert(MouseButton::Left, Slot::Primary)
+            .insert(MouseButton::Right, Slot::Secondary);
+        input_map
+    }
 }
 
 #[derive(Bundle)]
 struct PlayerBundle {
     player: Player,
     #[bundle]
-    sprite: SpriteBundle,
+    sprite: SpriteSheetBundle,
+    visual_states: VisualStates,
+    velocity: Velocity,
+    thrust: Thrust,
+    banking: Banking,
+    engine_trail: ParticleEmitter,
+    boundary_behavior: BoundaryBehavior,
     movement_input_map: InputMap<Movement>,
     movement_action_state: ActionState<Movement>,
     slot_input_map: InputMap<Slot>,
@@ -59,6 +215,19 @@ struct PlayerBundle {
     ability_slot_map: AbilitySlotMap,
 }
 
+/// Where the player starts each run, and flies back in to after losing a life.
+pub const PLAYER_START: Vec2 = Vec2::new(0., -200.);
+
+/// The keys that move the player, which no slot can be bound to.
+pub const MOVEMENT_KEYS: [(KeyCode, Movement); 6] = [
+    (KeyCode::A, Movement::Left),
+    (KeyCode::D, Movement::Right),
+    (KeyCode::Left, Movement::Left),
+    (KeyCode::Right, Movement::Right),
+    (KeyCode::Up, Movement::Up),
+    (KeyCode::Down, Movement::Down),
+];
+
 pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
@@ -70,63 +239,83 @@ impl Plugin for PlayerPlugin {
                     .in_base_set(CoreSet::PreUpdate)
                     .after(InputManagerSystem::ManualControl),
             )
-            .insert_resource(PlayerResource {
-                movement_speed: 250.,
-                health: 100.,
-                evades: 3,
-                bombs: 3,
-            })
-            .add_startup_system(Self::spawn_player)
-            .add_system(Self::handle_abilities)
-            .add_system(Self::handle_movement)
-            .add_system(Self::wrap_player_around_window)
-            .add_system(Self::handle_evasion);
+            .init_resource::<PlayerResource>()
+            .init_resource::<SlotBindings>()
+            // Reset on the way in rather than out, so the game-over screen can sum up the run
+            .add_systems(
+                (
+                    Self::reset_run.after(DifficultySystem),
+                    Self::spawn_player.after(Self::reset_run),
+                )
+                    .in_schedule(OnEnter(GameState::Playing)),
+            )
+            .add_system(despawn_all::<Player>.in_schedule(OnExit(GameState::Playing)))
+            .add_system(Self::apply_slot_bindings)
+            .add_systems(
+                (
+                    Self::handle_abilities,
+                    Self::handle_movement.before(MovementSystem),
+                    Self::keep_player_in_vertical_band.after(MovementSystem),
+                )
+                    .in_set(OnUpdate(GameState::Playing)),
+            );
     }
 }
 
 impl PlayerPlugin {
     fn spawn_player(
         mut commands: Commands,
-        asset_server: Res<AssetServer>,
+        sprite_registry: Res<SpriteRegistry>,
+        player_resource: Res<PlayerResource>,
+        slot_bindings: Res<SlotBindings>,
         // window_query: Query<&Window, With<PrimaryWindow>>,
     ) {
         // let window = window_query.single();
 
-        let player_sprite = SpriteBundle {
-            transform: Transform {
-                translation: Vec3::new(0., -200., 0.),
+        let player_sprite = sprite_registry.sprite_sheet_bundle(
+            SpriteName::PlayerShip,
+            Transform {
+                translation: PLAYER_START.extend(0.),
                 ..default()
             },
-            texture: asset_server.load("Ships/ship_0004.png"),
-            ..default()
-        };
+        );
 
         let mut ability_slot_map = AbilitySlotMap::default();
         ability_slot_map.insert(Slot::Primary, Ability::ShootBullet);
         ability_slot_map.insert(Slot::Secondary, Ability::Bomb);
         ability_slot_map.insert(Slot::Ability1, Ability::Evade);
+        ability_slot_map.insert(Slot::Ability2, Ability::HomingMissile);
 
         let player_bundle = PlayerBundle {
             player: Player,
             sprite: player_sprite,
-            movement_input_map: InputMap::new([
-                (KeyCode::A, Movement::Left),
-                (KeyCode::D, Movement::Right),
-                (KeyCode::Left, Movement::Left),
-                (KeyCode::Right, Movement::Right),
-            ]),
+            visual_states: VisualStates::new(Appearance::new(SpriteName::PlayerShip))
+                .with(
+                    VisualState::PoweredUp,
+                    Appearance::new(SpriteName::PlayerShip).with_tint(Color::GOLD),
+                )
+                .with(
+                    VisualState::Damaged,
+                    Appearance::new(SpriteName::PlayerShip)
+                        .with_tint(Color::RED)
+                        .with_blink_rate(20.),
+                )
+                .with(
+                    VisualState::Invulnerable,
+                    Appearance::new(SpriteName::PlayerShip).with_blink_rate(8.),
+                )
+                .with(
+                    VisualState::Evading,
+                    Appearance::new(SpriteName::PlayerShipEvading).with_blink_rate(12.),
+                ),
+            velocity: Velocity::default(),
+            thrust: Thrust::default(),
+            banking: Banking::default(),
+            engine_trail: ParticleEmitter::new(ParticleKind::EngineTrail, Vec2::new(0., -20.)),
+            boundary_behavior: player_resource.boundary_behavior,
+            movement_input_map: InputMap::new(MOVEMENT_KEYS),
             movement_action_state: ActionState::default(),
-            slot_input_map: InputMap::new([
-                (KeyCode::Space, Slot::Ability1),
-                (KeyCode::Q, Slot::Ability2),
-                (KeyCode::W, Slot::Ability3),
-                (KeyCode::E, Slot::Ability4),
-            ])
-            .insert(MouseButton::Left, Slot::Primary)
-            .insert(MouseButton::Right, Slot::Secondary)
-            .insert(KeyCode::Z, Slot::Primary)
-            .insert(KeyCode::X, Slot::Secondary)
-            .build(),
+            slot_input_map: slot_bindings.input_map(),
             slot_action_state: ActionState::default(),
             ability_action_state: ActionState::default(),
             ability_slot_map,
@@ -135,6 +324,37 @@ impl PlayerPlugin {
         commands.spawn(player_bundle);
     }
 
+    fn reset_run(
+        mut player_resource: ResMut<PlayerResource>,
+        difficulty_scaling: Res<DifficultyScaling>,
+    ) {
+        player_resource.reset_run();
+
+        // The difficulty gives more or less to fall back on
+        player_resource.bombs = DifficultyScaling::starting_count(
+            player_resource.bombs,
+            difficulty_scaling.extra_bombs,
+        );
+        player_resource.evades = DifficultyScaling::starting_count(
+            player_resource.evades,
+            difficulty_scaling.extra_evades,
+        );
+    }
+
+    /// Rebinds the player's keys as soon as the bindings change.
+    fn apply_slot_bindings(
+        slot_bindings: Res<SlotBindings>,
+        mut input_map_query: Query<&mut InputMap<Slot>, With<Player>>,
+    ) {
+        if !slot_bindings.is_changed() {
+            return;
+        }
+
+        for mut input_map in input_map_query.iter_mut() {
+            *input_map = slot_bindings.input_map();
+        }
+    }
+
     fn copy_action_state(
         mut query: Query<(
             &ActionState<Slot>,
@@ -153,99 +373,134 @@ impl PlayerPlugin {
     }
 
     fn handle_abilities(
-        mut ev_shoot_bullet: EventWriter<ShootBulletEvent>,
-        mut ev_shoot_bomb: EventWriter<ShootBombEvent>,
+        mut ev_fire: EventWriter<FireProjectileEvent>,
         mut ev_evade: EventWriter<EvadeEvent>,
         ability_query: Query<&ActionState<Ability>>,
-        player_query: Query<&Transform, With<Player>>,
+        // Out of the player's hands until they're back
+        player_query: Query<&Transform, (With<Player>, Without<Respawn>)>,
+        mut player_resource: ResMut<PlayerResource>,
     ) {
-        let player_transform = player_query.single();
+        let Ok(player_transform) = player_query.get_single() else {
+            return;
+        };
+        let modifiers = player_resource.projectile_modifiers;
+        let fire = |kind| FireProjectileEvent {
+            kind,
+            owner: ProjectileOwner::Player,
+            origin: *player_transform,
+            modifiers,
+        };
 
         for ability_state in ability_query.iter() {
             for ability in ability_state.get_just_pressed() {
                 match ability {
-                    Ability::ShootBullet => {
-                        ev_shoot_bullet.send(ShootBulletEvent(*player_transform))
+                    Ability::ShootBullet => ev_fire.send(fire(ProjectileKind::Bullet)),
+                    Ability::Bomb => {
+                        if player_resource.bombs > 0 {
+                            player_resource.bombs -= 1;
+                            ev_fire.send(fire(ProjectileKind::Bomb));
+                        }
+
+                        debug!("Bombs left: {}", player_resource.bombs);
+                    }
+                    Ability::HomingMissile => {
+                        if player_resource.missiles > 0 {
+                            player_resource.missiles -= 1;
+                            ev_fire.send(fire(ProjectileKind::Missile));
+                        }
+
+                        debug!("Missiles left: {}", player_resource.missiles);
                     }
-                    Ability::Bomb => ev_shoot_bomb.send(ShootBombEvent(*player_transform)),
                     Ability::Evade => ev_evade.send(EvadeEvent),
                 }
             }
         }
     }
 
+    #[allow(clippy::type_complexity)]
     fn handle_movement(
-        mut player: Query<&mut Transform, With<Player>>,
-        action_query: Query<&ActionState<Movement>, With<Player>>,
+        mut player: Query<
+            (&mut Velocity, &mut Thrust, &ActionState<Movement>),
+            (With<Player>, Without<Respawn>),
+        >,
         player_resource: Res<PlayerResource>,
         time: Res<Time>,
+        mut ev_sfx: EventWriter<PlaySfxEvent>,
     ) {
-        let mut player_transform = player.single_mut();
-        let action_state = action_query.single();
+        let Ok((mut velocity, mut thrust, action_state)) = player.get_single_mut() else {
+            return;
+        };
 
-        if action_state.pressed(Movement::Left) {
-            player_transform.translation.x -= player_resource.movement_speed * time.delta_seconds();
-        } else if action_state.pressed(Movement::Right) {
-            player_transform.translation.x += player_resource.movement_speed * time.delta_seconds();
+        // Holding both directions on an axis cancels them out
+        let axis = |negative, positive| match (
+            action_state.pressed(negative),
+            action_state.pressed(positive),
+        ) {
+            (true, false) => -1.,
+            (false, true) => 1.,
+            _ => 0.,
+        };
+        let mut direction = Vec2::new(axis(Movement::Left, Movement::Right), 0.);
+        if player_resource.vertical_band.is_some() {
+            direction.y = axis(Movement::Down, Movement::Up);
         }
-    }
 
-    fn wrap_player_around_window(
-        mut player: Query<&mut Transform, With<Player>>,
-        window_query: Query<&Window, With<PrimaryWindow>>,
-    ) {
-        let window = window_query.single();
-        let mut player_transform = player.single_mut();
+        let target_velocity = direction.normalize_or_zero() * player_resource.movement_speed;
 
-        // Calculate the distance from the player to the edge of the window
-        let distance_to_edge = window.width() / 2. - player_transform.translation.x.abs();
+        velocity.0 = match player_resource.movement_model {
+            MovementModel::Instant => target_velocity,
+            MovementModel::Inertial {
+                acceleration,
+                friction,
+            } => {
+                let delta_seconds = time.delta_seconds();
 
-        // If the player is outside the window, wrap them around to the other side
-        if distance_to_edge < 0. {
-            // Calculate the offset to move the player by to wrap them around to the other side of the window
-            let offset = -player_transform.translation.x.signum() * (window.width() / 2. - 1.);
-            player_transform.translation.x = offset;
+                if direction == Vec2::ZERO {
+                    let speed = (velocity.length() - friction * delta_seconds).max(0.);
+                    velocity.normalize_or_zero() * speed
+                } else {
+                    let change = (target_velocity - velocity.0)
+                        .clamp_length_max(acceleration * delta_seconds);
+                    (velocity.0 + change).clamp_length_max(player_resource.movement_speed)
+                }
+            }
+        };
+
+        // Fire the thrusters whenever the player steers somewhere new
+        if direction != thrust.direction && direction != Vec2::ZERO {
+            ev_sfx.send(PlaySfxEvent(Sfx::Thruster));
         }
+
+        thrust.direction = direction;
+        thrust.throttle = if player_resource.movement_speed > 0. {
+            (velocity.length() / player_resource.movement_speed).min(1.)
+        } else {
+            0.
+        };
     }
 
-    fn handle_evasion(
-        mut commands: Commands,
-        mut player_query: Query<(Entity, &Transform), With<Player>>,
-        time: Res<Time>,
-        mut evade_timer_query: Query<&mut EvadeTimer>,
-        asset_server: Res<AssetServer>,
+    #[allow(clippy::type_complexity)]
+    fn keep_player_in_vertical_band(
+        // Flying back in starts from below the band
+        mut player: Query<(&mut Transform, &mut Velocity), (With<Player>, Without<Respawn>)>,
+        player_resource: Res<PlayerResource>,
+        play_area: Res<PlayArea>,
     ) {
-        let (player_entity, player_transform) = player_query.single_mut();
-
-        if let Ok(mut evade_timer) = evade_timer_query.get_single_mut() {
-            if evade_timer.time.just_finished() {
-                let original_player_sprite = SpriteBundle {
-                    transform: Transform {
-                        translation: player_transform.translation,
-                        ..default()
-                    },
-                    texture: asset_server.load("Ships/ship_0004.png"),
-                    ..default()
-                };
-
-                commands
-                    .entity(player_entity)
-                    .insert(original_player_sprite) // Revert to the original sprite
-                    .remove::<EvadeTimer>(); // Remove the evade timer
-            } else {
-                evade_timer.time.tick(time.delta());
-
-                let evasion_player_sprite = SpriteBundle {
-                    transform: Transform {
-                        translation: player_transform.translation,
-                        ..default()
-                    },
-                    texture: asset_server.load("Ships/ship_0016.png"),
-                    ..default()
-                };
-
-                commands.entity(player_entity).insert(evasion_player_sprite);
-            }
+        let Some(vertical_band) = player_resource.vertical_band else {
+            return;
+        };
+
+        let Ok((mut player_transform, mut velocity)) = player.get_single_mut() else {
+            return;
+        };
+
+        let bottom = -play_area.half_height();
+        let top = bottom + play_area.height * vertical_band;
+        let y = player_transform.translation.y;
+
+        if y < bottom || y > top {
+            player_transform.translation.y = y.clamp(bottom, top);
+            velocity.y = 0.;
         }
     }
 }
@@ -0,0 +1,80 @@
+use std::hash::Hash;
+
+use bevy::{prelude::*, utils::HashMap};
+
+/// An entity that is parked for reuse instead of despawned, so that entities which come and
+/// go in large numbers don't keep moving between archetypes.
+///
+/// Parked entities keep all of their components, so systems acting on pooled entities
+/// should skip those that aren't active.
+#[derive(Component, Debug)]
+pub struct Pooled {
+    active: bool,
+}
+
+impl Pooled {
+    pub fn active() -> Self {
+        Self { active: true }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Hands the entity back to its pool to be parked.
+    pub fn release(&mut self) {
+        self.active = false;
+    }
+}
+
+/// Despawns `entity`, or releases it if it belongs to a pool.
+pub fn despawn_or_release(
+    commands: &mut Commands,
+    entity: Entity,
+    pooled: Option<Mut<'_, Pooled>>,
+) {
+    match pooled {
+        // Only flag a change once, so the entity isn't parked twice
+        Some(mut pooled) => {
+            if pooled.is_active() {
+                pooled.release();
+            }
+        }
+        None => commands.entity(entity).despawn_recursive(),
+    }
+}
+
+/// Parked entities, grouped by `K` so that an entity is only reused for the same kind of
+/// thing and keeps the same set of components.
+#[derive(Resource, Debug)]
+pub struct EntityPool<K> {
+    parked: HashMap<K, Vec<Entity>>,
+}
+
+impl<K> Default for EntityPool<K> {
+    fn default() -> Self {
+        Self {
+            parked: HashMap::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash> EntityPool<K> {
+    /// A parked entity of `kind` to reuse, if there is one.
+    pub fn take(&mut self, kind: K) -> Option<Entity> {
+        self.parked.get_mut(&kind)?.pop()
+    }
+
+    pub fn park(&mut self, kind: K, entity: Entity) {
+        self.parked.entry(kind).or_default().push(entity);
+    }
+
+    /// The number of parked entities of every kind.
+    pub fn len(&self) -> usize {
+        self.parked.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
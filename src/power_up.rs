@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+
+use crate::{
+    boundary::BoundaryBehavior,
+    despawn_all,
+    enemy::EnemyDestroyedEvent,
+    movement::Velocity,
+    player::{Player, PlayerResource},
+    projectile::ProjectileModifiers,
+    sfx::{PlaySfxEvent, Sfx},
+    sprites::{SpriteName, SpriteRegistry},
+    visual_state::{VisualState, VisualStates},
+    GameState,
+};
+
+/// A pickup that adds to the [`ProjectileModifiers`] of everything the player fires.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PowerUp {
+    Pierce,
+    Ricochet,
+    Chain,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 3] = [PowerUp::Pierce, PowerUp::Ricochet, PowerUp::Chain];
+
+    pub fn modifiers(&self) -> ProjectileModifiers {
+        match self {
+            PowerUp::Pierce => ProjectileModifiers {
+                pierce: 1,
+                ..default()
+            },
+            PowerUp::Ricochet => ProjectileModifiers {
+                ricochets: 1,
+                ..default()
+            },
+            PowerUp::Chain => ProjectileModifiers {
+                chain: 1,
+                chain_range: 150.,
+                ..default()
+            },
+        }
+    }
+
+    pub fn sprite(&self) -> SpriteName {
+        match self {
+            PowerUp::Pierce => SpriteName::PowerUpPierce,
+            PowerUp::Ricochet => SpriteName::PowerUpRicochet,
+            PowerUp::Chain => SpriteName::PowerUpChain,
+        }
+    }
+}
+
+#[derive(Bundle)]
+struct PowerUpBundle {
+    power_up: PowerUp,
+    #[bundle]
+    sprite: SpriteSheetBundle,
+    velocity: Velocity,
+    boundary_behavior: BoundaryBehavior,
+}
+
+#[derive(Resource)]
+pub struct PowerUpResource {
+    /// A power-up drops from every this many enemies shot down (0 disables drops)
+    pub kills_per_drop: u32,
+    /// The distance a power-up falls per second
+    pub fall_speed: f32,
+    /// The radius of a power-up (used for collision detection with the player)
+    pub radius: f32,
+}
+
+/// Kills since the last drop, and which [`PowerUp`] drops next.
+#[derive(Resource, Default)]
+struct PowerUpDrops {
+    kills: u32,
+    next: usize,
+}
+
+pub struct PowerUpPlugin;
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PowerUpResource {
+            kills_per_drop: 5,
+            fall_speed: 100.,
+            radius: 20.,
+        })
+        .init_resource::<PowerUpDrops>()
+        .add_systems(
+            (Self::drop_power_ups, Self::collect_power_ups).in_set(OnUpdate(GameState::Playing)),
+        )
+        .add_systems(
+            (despawn_all::<PowerUp>, Self::reset_drops).in_schedule(OnExit(GameState::Playing)),
+        );
+    }
+}
+
+impl PowerUpPlugin {
+    fn reset_drops(mut drops: ResMut<PowerUpDrops>) {
+        *drops = PowerUpDrops::default();
+    }
+
+    fn drop_power_ups(
+        mut commands: Commands,
+        sprite_registry: Res<SpriteRegistry>,
+        mut ev_enemy_destroyed: EventReader<EnemyDestroyedEvent>,
+        mut drops: ResMut<PowerUpDrops>,
+        power_up_resource: Res<PowerUpResource>,
+    ) {
+        for ev in ev_enemy_destroyed.iter() {
+            if power_up_resource.kills_per_drop == 0 {
+                continue;
+            }
+
+            drops.kills += 1;
+            if drops.kills < power_up_resource.kills_per_drop {
+                continue;
+            }
+
+            // Take turns so every kind of power-up shows up
+            let power_up = PowerUp::ALL[drops.next % PowerUp::ALL.len()];
+            drops.kills = 0;
+            drops.next += 1;
+
+            commands.spawn(PowerUpBundle {
+                power_up,
+                sprite: sprite_registry.sprite_sheet_bundle(
+                    power_up.sprite(),
+                    Transform::from_translation(ev.0.extend(0.)),
+                ),
+                velocity: Velocity(Vec2::new(0., -power_up_resource.fall_speed)),
+                boundary_behavior: BoundaryBehavior::Despawn,
+            });
+        }
+    }
+
+    fn collect_power_ups(
+        mut commands: Commands,
+        power_up_query: Query<(Entity, &Transform, &PowerUp)>,
+        mut player_query: Query<(&Transform, &mut VisualStates), With<Player>>,
+        power_up_resource: Res<PowerUpResource>,
+        mut player_resource: ResMut<PlayerResource>,
+        mut ev_sfx: EventWriter<PlaySfxEvent>,
+    ) {
+        let Ok((player_transform, mut visual_states)) = player_query.get_single_mut() else {
+            return;
+        };
+
+        for (entity, transform, power_up) in power_up_query.iter() {
+            if transform.translation.distance(player_transform.translation)
+                <= power_up_resource.radius
+            {
+                ev_sfx.send(PlaySfxEvent(Sfx::PowerUp));
+
+                player_resource.projectile_modifiers =
+                    player_resource.projectile_modifiers + power_up.modifiers();
+                visual_states.insert_for(VisualState::PoweredUp, 1.);
+                commands.entity(entity).despawn_recursive();
+
+                debug!("Powered up: {power_up:?}");
+            }
+        }
+    }
+}
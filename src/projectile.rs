@@ -0,0 +1,599 @@
+use std::ops::Add;
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    animation::{Animation, SpriteAnimation},
+    boundary::{BoundaryBehavior, BoundarySystem},
+    camera_effects::CameraShakeEvent,
+    despawn_all,
+    enemy::{Enemy, EnemyDestroyedEvent, Health},
+    evade::EvadeTimer,
+    lives::Invulnerable,
+    movement::{MovementSystem, Velocity},
+    particles::{ParticleKind, SpawnParticlesEvent},
+    play_area::PlayArea,
+    player::{Player, PlayerResource},
+    pool::{EntityPool, Pooled},
+    score::ShotLandedEvent,
+    sfx::{PlaySfxEvent, Sfx},
+    sprites::{SpriteName, SpriteRegistry},
+    visual_state::{VisualState, VisualStates},
+    GameState,
+};
+
+/// A type of projectile, whose behaviour is looked up in the [`ProjectileResource`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProjectileKind {
+    Bullet,
+    Bomb,
+    Missile,
+    /// Fired down at the player by enemies
+    EnemyShot,
+}
+
+/// Who fired a projectile, which decides what it can hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectileOwner {
+    Player,
+    Enemy,
+}
+
+/// Extra behaviour layered onto a projectile. Modifiers from different sources, like a
+/// projectile's spec and the player's power-ups, stack by adding them together.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProjectileModifiers {
+    /// How many more enemies the projectile passes through after its first hit
+    pub pierce: u32,
+    /// How many times the projectile bounces off the edges of the play area before leaving it
+    pub ricochets: u32,
+    /// How many further enemies each hit arcs to, each the nearest one within `chain_range`
+    /// of the last
+    pub chain: u32,
+    pub chain_range: f32,
+}
+
+impl Add for ProjectileModifiers {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            pierce: self.pierce + other.pierce,
+            ricochets: self.ricochets + other.ricochets,
+            chain: self.chain + other.chain,
+            chain_range: self.chain_range.max(other.chain_range),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ProjectileSpec {
+    /// The distance travelled per second along each axis
+    pub velocity: Vec2,
+    /// Where the projectile appears relative to whoever fired it
+    pub spawn_offset: Vec2,
+    /// How long the projectile lasts, in seconds (`None` lasts until it leaves the play area)
+    pub lifetime: Option<f32>,
+    pub damage: f32,
+    /// The radius of the projectile (used for collision detection)
+    pub radius: f32,
+    pub modifiers: ProjectileModifiers,
+    /// The maximum rate at which the projectile turns towards the nearest enemy, in
+    /// radians per second (`None` flies straight)
+    pub homing: Option<f32>,
+    pub sprite: SpriteName,
+    /// Plays over `sprite` while the projectile flies
+    pub animation: Option<Animation>,
+    pub fire_sfx: Sfx,
+    pub hit_sfx: Sfx,
+    pub hit_particles: ParticleKind,
+    /// How hard the camera shakes on a hit, from 0 to 1
+    pub hit_trauma: f32,
+}
+
+/// The behaviour of every [`ProjectileKind`].
+#[derive(Resource)]
+pub struct ProjectileResource {
+    pub kinds: HashMap<ProjectileKind, ProjectileSpec>,
+    /// Park spent projectiles for reuse instead of despawning them
+    pub pooling: bool,
+}
+
+impl Default for ProjectileResource {
+    fn default() -> Self {
+        Self {
+            kinds: HashMap::from([
+                (
+                    ProjectileKind::Bullet,
+                    ProjectileSpec {
+                        velocity: Vec2::new(0., 300.),
+                        spawn_offset: Vec2::new(0., 20.),
+                        lifetime: None,
+                        damage: 100.,
+                        radius: 10.,
+                        modifiers: ProjectileModifiers::default(),
+                        homing: None,
+                        sprite: SpriteName::Bullet,
+                        animation: None,
+                        fire_sfx: Sfx::ShootBullet,
+                        hit_sfx: Sfx::BulletHit,
+                        hit_particles: ParticleKind::BulletImpact,
+                        hit_trauma: 0.,
+                    },
+                ),
+                (
+                    ProjectileKind::Bomb,
+                    ProjectileSpec {
+                        velocity: Vec2::new(0., 200.),
+                        spawn_offset: Vec2::new(0., 20.),
+                        lifetime: None,
+                        damage: 100.,
+                        radius: 50.,
+                        modifiers: ProjectileModifiers::default(),
+                        homing: None,
+                        sprite: SpriteName::Bomb,
+                        animation: Some(Animation::BombSpin),
+                        fire_sfx: Sfx::ShootBomb,
+                        hit_sfx: Sfx::BombHit,
+                        hit_particles: ParticleKind::BombBlast,
+                        hit_trauma: 0.6,
+                    },
+                ),
+                (
+                    ProjectileKind::Missile,
+                    ProjectileSpec {
+                        velocity: Vec2::new(0., 250.),
+                        spawn_offset: Vec2::new(0., 20.),
+                        lifetime: Some(4.),
+                        damage: 100.,
+                        radius: 12.,
+                        modifiers: ProjectileModifiers::default(),
+                        homing: Some(4.),
+                        sprite: SpriteName::Missile,
+                        animation: None,
+                        fire_sfx: Sfx::ShootMissile,
+                        hit_sfx: Sfx::BulletHit,
+                        hit_particles: ParticleKind::BulletImpact,
+                        hit_trauma: 0.,
+                    },
+                ),
+                (
+                    ProjectileKind::EnemyShot,
+                    ProjectileSpec {
+                        velocity: Vec2::new(0., -200.),
+                        spawn_offset: Vec2::new(0., -20.),
+                        lifetime: None,
+                        damage: 25.,
+                        radius: 10.,
+                        modifiers: ProjectileModifiers::default(),
+                        homing: None,
+                        sprite: SpriteName::Bullet,
+                        animation: None,
+                        fire_sfx: Sfx::ShootBullet,
+                        hit_sfx: Sfx::BulletHit,
+                        hit_particles: ParticleKind::BulletImpact,
+                        hit_trauma: 0.,
+                    },
+                ),
+            ]),
+            pooling: true,
+        }
+    }
+}
+
+impl ProjectileResource {
+    pub fn spec(&self, kind: ProjectileKind) -> &ProjectileSpec {
+        &self.kinds[&kind]
+    }
+}
+
+/// Fires a projectile of `kind` from `origin`.
+pub struct FireProjectileEvent {
+    pub kind: ProjectileKind,
+    pub owner: ProjectileOwner,
+    pub origin: Transform,
+    /// Added on top of the modifiers in the kind's spec
+    pub modifiers: ProjectileModifiers,
+}
+
+#[derive(Component, Debug)]
+pub struct Projectile {
+    pub kind: ProjectileKind,
+    pub owner: ProjectileOwner,
+    /// What's left of the projectile's modifiers, used up as it pierces and ricochets
+    pub modifiers: ProjectileModifiers,
+    /// Every enemy the projectile has damaged, which it won't damage again
+    pub hits: HashSet<Entity>,
+}
+
+/// Steers the projectile's velocity towards `target`, picking the nearest enemy whenever
+/// it has none.
+#[derive(Component, Debug)]
+pub struct Homing {
+    /// In radians per second
+    pub turn_rate: f32,
+    pub target: Option<Entity>,
+}
+
+/// Despawns the entity when the timer runs out.
+#[derive(Component)]
+pub struct Lifetime(pub Timer);
+
+/// The sound, particles and camera shake of a projectile hitting something.
+#[derive(SystemParam)]
+struct HitFeedback<'w> {
+    ev_sfx: EventWriter<'w, PlaySfxEvent>,
+    ev_particles: EventWriter<'w, SpawnParticlesEvent>,
+    ev_shake: EventWriter<'w, CameraShakeEvent>,
+}
+
+impl HitFeedback<'_> {
+    /// Shakes the camera by at least `min_trauma`, even if the projectile wouldn't.
+    fn send(&mut self, spec: &ProjectileSpec, position: Vec2, min_trauma: f32) {
+        self.ev_sfx.send(PlaySfxEvent(spec.hit_sfx));
+        self.ev_particles.send(SpawnParticlesEvent {
+            kind: spec.hit_particles,
+            position,
+        });
+
+        let trauma = spec.hit_trauma.max(min_trauma);
+        if trauma > 0. {
+            self.ev_shake.send(CameraShakeEvent(trauma));
+        }
+    }
+}
+
+#[derive(Bundle)]
+struct ProjectileBundle {
+    projectile: Projectile,
+    #[bundle]
+    sprite: SpriteSheetBundle,
+    velocity: Velocity,
+    boundary_behavior: BoundaryBehavior,
+    pooled: Pooled,
+}
+
+pub struct ProjectilePlugin;
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProjectileResource>()
+            .init_resource::<EntityPool<ProjectileKind>>()
+            .add_event::<FireProjectileEvent>()
+            .add_systems(
+                (
+                    Self::fire_projectiles,
+                    Self::expire_projectiles,
+                    Self::steer_homing_projectiles.before(MovementSystem),
+                    Self::count_ricochets
+                        .after(MovementSystem)
+                        .before(BoundarySystem),
+                    Self::check_enemy_hits,
+                    Self::check_player_hits,
+                )
+                    .in_set(OnUpdate(GameState::Playing)),
+            )
+            .add_system(Self::recycle_projectiles.in_base_set(CoreSet::PostUpdate))
+            .add_system(despawn_all::<Projectile>.in_schedule(OnExit(GameState::Playing)));
+    }
+}
+
+impl ProjectilePlugin {
+    fn fire_projectiles(
+        mut commands: Commands,
+        sprite_registry: Res<SpriteRegistry>,
+        projectile_resource: Res<ProjectileResource>,
+        mut projectile_pool: ResMut<EntityPool<ProjectileKind>>,
+        mut ev_fire: EventReader<FireProjectileEvent>,
+        mut ev_sfx: EventWriter<PlaySfxEvent>,
+    ) {
+        for ev in ev_fire.iter() {
+            let spec = projectile_resource.spec(ev.kind);
+            let translation = ev.origin.translation.truncate() + spec.spawn_offset;
+            let modifiers = spec.modifiers + ev.modifiers;
+
+            ev_sfx.send(PlaySfxEvent(spec.fire_sfx));
+            let bundle = ProjectileBundle {
+                projectile: Projectile {
+                    kind: ev.kind,
+                    owner: ev.owner,
+                    modifiers,
+                    hits: HashSet::default(),
+                },
+                sprite: sprite_registry.sprite_sheet_bundle(
+                    spec.sprite,
+                    Transform::from_translation(translation.extend(0.)),
+                ),
+                velocity: Velocity(spec.velocity),
+                boundary_behavior: if modifiers.ricochets > 0 {
+                    BoundaryBehavior::Bounce
+                } else {
+                    BoundaryBehavior::Despawn
+                },
+                pooled: Pooled::active(),
+            };
+
+            let parked = std::iter::from_fn(|| projectile_pool.take(ev.kind))
+                .find(|&entity| commands.get_entity(entity).is_some());
+            let mut projectile = match parked {
+                Some(entity) => {
+                    let mut projectile = commands.entity(entity);
+                    projectile.insert(bundle);
+                    projectile
+                }
+                None => commands.spawn(bundle),
+            };
+
+            if let Some(lifetime) = spec.lifetime {
+                projectile.insert(Lifetime(Timer::from_seconds(lifetime, TimerMode::Once)));
+            }
+            if let Some(turn_rate) = spec.homing {
+                projectile.insert(Homing {
+                    turn_rate,
+                    target: None,
+                });
+            }
+            if let Some(animation) = spec.animation {
+                projectile.insert(SpriteAnimation::new(animation));
+            }
+        }
+    }
+
+    fn expire_projectiles(mut query: Query<(&mut Lifetime, &mut Pooled)>, time: Res<Time>) {
+        for (mut lifetime, mut pooled) in query.iter_mut() {
+            if pooled.is_active() && lifetime.0.tick(time.delta()).finished() {
+                pooled.release();
+            }
+        }
+    }
+
+    /// Parks released projectiles out of the way, or despawns them when not pooling.
+    #[allow(clippy::type_complexity)]
+    fn recycle_projectiles(
+        mut commands: Commands,
+        mut query: Query<
+            (
+                Entity,
+                &Projectile,
+                &Pooled,
+                &mut Transform,
+                &mut Velocity,
+                &mut Visibility,
+            ),
+            Changed<Pooled>,
+        >,
+        projectile_resource: Res<ProjectileResource>,
+        mut projectile_pool: ResMut<EntityPool<ProjectileKind>>,
+    ) {
+        for (entity, projectile, pooled, mut transform, mut velocity, mut visibility) in
+            query.iter_mut()
+        {
+            if pooled.is_active() {
+                continue;
+            }
+
+            if !projectile_resource.pooling {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+
+            // Parked in the middle of the play area, where boundaries leave it alone
+            *transform = Transform::IDENTITY;
+            velocity.0 = Vec2::ZERO;
+            *visibility = Visibility::Hidden;
+            projectile_pool.park(projectile.kind, entity);
+        }
+    }
+
+    fn steer_homing_projectiles(
+        mut homing_query: Query<
+            (&mut Homing, &mut Velocity, &mut Transform, &Pooled),
+            Without<Enemy>,
+        >,
+        enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+        time: Res<Time>,
+    ) {
+        for (mut homing, mut velocity, mut transform, pooled) in homing_query.iter_mut() {
+            if !pooled.is_active() {
+                continue;
+            }
+
+            let position = transform.translation.truncate();
+
+            // Retarget once the current target is destroyed
+            if homing
+                .target
+                .is_none_or(|target| !enemy_query.contains(target))
+            {
+                homing.target = enemy_query
+                    .iter()
+                    .min_by(|(_, a), (_, b)| {
+                        let a = a.translation.truncate().distance_squared(position);
+                        let b = b.translation.truncate().distance_squared(position);
+                        a.total_cmp(&b)
+                    })
+                    .map(|(enemy, _)| enemy);
+            }
+
+            let Some((_, target_transform)) = homing
+                .target
+                .and_then(|target| enemy_query.get(target).ok())
+            else {
+                continue;
+            };
+
+            let to_target = target_transform.translation.truncate() - position;
+            if to_target == Vec2::ZERO || velocity.0 == Vec2::ZERO {
+                continue;
+            }
+
+            let max_turn = homing.turn_rate * time.delta_seconds();
+            let turn = velocity
+                .0
+                .angle_between(to_target)
+                .clamp(-max_turn, max_turn);
+            velocity.0 = Vec2::from_angle(turn).rotate(velocity.0);
+
+            // The sprite points up
+            transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_between(velocity.0));
+        }
+    }
+
+    fn count_ricochets(
+        mut query: Query<(&Transform, &mut Projectile, &mut BoundaryBehavior)>,
+        play_area: Res<PlayArea>,
+    ) {
+        let half_extents = Vec2::new(play_area.half_width(), play_area.half_height());
+
+        for (transform, mut projectile, mut boundary_behavior) in query.iter_mut() {
+            if *boundary_behavior != BoundaryBehavior::Bounce
+                || !transform
+                    .translation
+                    .truncate()
+                    .abs()
+                    .cmpgt(half_extents)
+                    .any()
+            {
+                continue;
+            }
+
+            // Leave the play area instead once out of ricochets
+            if projectile.modifiers.ricochets == 0 {
+                *boundary_behavior = BoundaryBehavior::Despawn;
+            } else {
+                projectile.modifiers.ricochets -= 1;
+            }
+        }
+    }
+
+    fn check_enemy_hits(
+        mut commands: Commands,
+        mut projectile_query: Query<(&Transform, &mut Projectile, &mut Pooled)>,
+        mut enemy_query: Query<(Entity, &Transform, &mut Health), With<Enemy>>,
+        projectile_resource: Res<ProjectileResource>,
+        mut hit_feedback: HitFeedback,
+        mut ev_enemy_destroyed: EventWriter<EnemyDestroyedEvent>,
+        mut ev_shot_landed: EventWriter<ShotLandedEvent>,
+    ) {
+        for (projectile_transform, mut projectile, mut pooled) in projectile_query.iter_mut() {
+            if !pooled.is_active() || projectile.owner != ProjectileOwner::Player {
+                continue;
+            }
+
+            let spec = projectile_resource.spec(projectile.kind);
+            let position = projectile_transform.translation.truncate();
+
+            let alive_enemies: Vec<(Entity, Vec2)> = enemy_query
+                .iter()
+                .filter(|(_, _, health)| health.0 > 0.)
+                .map(|(enemy, transform, _)| (enemy, transform.translation.truncate()))
+                .collect();
+
+            let touching: Vec<_> = alive_enemies
+                .iter()
+                .copied()
+                .filter(|(enemy, enemy_position)| {
+                    !projectile.hits.contains(enemy)
+                        && enemy_position.distance(position) <= spec.radius
+                })
+                .collect();
+
+            for (enemy, enemy_position) in touching {
+                // Already damaged by an earlier chain this frame
+                if projectile.hits.contains(&enemy) {
+                    continue;
+                }
+
+                hit_feedback.send(spec, position, 0.);
+                if projectile.hits.is_empty() {
+                    ev_shot_landed.send(ShotLandedEvent(projectile.kind));
+                }
+
+                let mut targets = vec![(enemy, enemy_position)];
+                projectile.hits.insert(enemy);
+
+                for _ in 0..projectile.modifiers.chain {
+                    let (_, from) = targets[targets.len() - 1];
+                    let next = alive_enemies
+                        .iter()
+                        .copied()
+                        .filter(|(enemy, to)| {
+                            !projectile.hits.contains(enemy)
+                                && to.distance(from) <= projectile.modifiers.chain_range
+                        })
+                        .min_by(|(_, a), (_, b)| a.distance(from).total_cmp(&b.distance(from)));
+
+                    let Some((next_enemy, next_position)) = next else {
+                        break;
+                    };
+                    targets.push((next_enemy, next_position));
+                    projectile.hits.insert(next_enemy);
+                }
+
+                for (target, target_position) in targets {
+                    let Ok((_, _, mut health)) = enemy_query.get_mut(target) else {
+                        continue;
+                    };
+
+                    health.0 -= spec.damage;
+                    if health.0 <= 0. {
+                        commands.entity(target).despawn_recursive();
+                        ev_enemy_destroyed.send(EnemyDestroyedEvent(target_position));
+                    }
+                }
+
+                if projectile.modifiers.pierce == 0 {
+                    pooled.release();
+                    break;
+                }
+                projectile.modifiers.pierce -= 1;
+            }
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn check_player_hits(
+        mut projectile_query: Query<(&Transform, &Projectile, &mut Pooled)>,
+        // An evading or freshly respawned player can't be hit
+        mut player_query: Query<
+            (&Transform, &mut VisualStates),
+            (With<Player>, Without<EvadeTimer>, Without<Invulnerable>),
+        >,
+        projectile_resource: Res<ProjectileResource>,
+        mut player_resource: ResMut<PlayerResource>,
+        mut hit_feedback: HitFeedback,
+    ) {
+        let Ok((player_transform, mut visual_states)) = player_query.get_single_mut() else {
+            return;
+        };
+
+        for (projectile_transform, projectile, mut pooled) in projectile_query.iter_mut() {
+            if !pooled.is_active() || projectile.owner != ProjectileOwner::Enemy {
+                continue;
+            }
+
+            let spec = projectile_resource.spec(projectile.kind);
+
+            if projectile_transform
+                .translation
+                .distance(player_transform.translation)
+                <= spec.radius
+            {
+                hit_feedback.send(
+                    spec,
+                    projectile_transform.translation.truncate(),
+                    player_resource.damage_trauma,
+                );
+
+                player_resource.health -= spec.damage;
+                visual_states.insert_for(VisualState::Damaged, 0.5);
+                pooled.release();
+
+                debug!("Health left: {}", player_resource.health);
+            }
+        }
+    }
+}
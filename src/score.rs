@@ -0,0 +1,180 @@
+use std::{fs, io, path::Path};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enemy::{EnemyDestroyedEvent, Waves},
+    projectile::{FireProjectileEvent, ProjectileKind, ProjectileOwner},
+    GameState,
+};
+
+/// Where the [`HighScores`] are kept between runs, relative to the working directory.
+pub const HIGH_SCORES_PATH: &str = "high_scores.ron";
+
+#[derive(Resource)]
+pub struct ScoreResource {
+    /// Added to the score for every enemy destroyed
+    pub points_per_kill: u32,
+}
+
+impl Default for ScoreResource {
+    fn default() -> Self {
+        Self {
+            points_per_kill: 100,
+        }
+    }
+}
+
+/// How the current run is going, or how the last one went once it's over.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct RunStats {
+    pub score: u32,
+    /// Every projectile the player has fired
+    pub shots_fired: u32,
+    /// The projectiles that hit at least one enemy
+    pub shots_landed: u32,
+}
+
+impl RunStats {
+    /// The fraction of shots that landed, from 0 to 1.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.
+        } else {
+            self.shots_landed as f32 / self.shots_fired as f32
+        }
+    }
+}
+
+/// Sent the first time a projectile of the player's hits an enemy.
+pub struct ShotLandedEvent(pub ProjectileKind);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    /// The wave the run ended on
+    pub wave: u32,
+}
+
+/// The best runs so far, best first.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// How many runs are kept.
+    pub const MAX_ENTRIES: usize = 5;
+
+    /// Adds a run if it makes the table, returning its place from 0. Runs that scored
+    /// nothing never do.
+    pub fn record(&mut self, score: u32, wave: u32) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+
+        // Ties go below the runs that got there first
+        let rank = self
+            .entries
+            .iter()
+            .position(|entry| score > entry.score)
+            .unwrap_or(self.entries.len());
+        if rank >= Self::MAX_ENTRIES {
+            return None;
+        }
+
+        self.entries.insert(rank, HighScore { score, wave });
+        self.entries.truncate(Self::MAX_ENTRIES);
+        Some(rank)
+    }
+
+    /// Reads the scores saved at `path`, or an empty table if there are none or they can't
+    /// be read.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+
+        match fs::read_to_string(path) {
+            Ok(contents) => ron::from_str(&contents).unwrap_or_else(|err| {
+                warn!("Ignoring unreadable high scores in {path:?}: {err}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = ron::ser::to_string_pretty(self, default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::write(path, contents)
+    }
+}
+
+/// Keeps the score and accuracy of each run in [`RunStats`].
+pub struct ScorePlugin;
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScoreResource>()
+            .init_resource::<RunStats>()
+            .add_event::<ShotLandedEvent>()
+            .add_system(Self::reset_stats.in_schedule(OnEnter(GameState::Playing)))
+            .add_systems(
+                (Self::count_shots, Self::score_kills).in_set(OnUpdate(GameState::Playing)),
+            );
+    }
+}
+
+impl ScorePlugin {
+    fn reset_stats(mut run_stats: ResMut<RunStats>) {
+        *run_stats = RunStats::default();
+    }
+
+    fn count_shots(
+        mut ev_fire: EventReader<FireProjectileEvent>,
+        mut ev_shot_landed: EventReader<ShotLandedEvent>,
+        mut run_stats: ResMut<RunStats>,
+    ) {
+        run_stats.shots_fired += ev_fire
+            .iter()
+            .filter(|ev| ev.owner == ProjectileOwner::Player)
+            .count() as u32;
+        run_stats.shots_landed += ev_shot_landed.iter().count() as u32;
+    }
+
+    fn score_kills(
+        mut ev_enemy_destroyed: EventReader<EnemyDestroyedEvent>,
+        score_resource: Res<ScoreResource>,
+        mut run_stats: ResMut<RunStats>,
+    ) {
+        run_stats.score +=
+            ev_enemy_destroyed.iter().count() as u32 * score_resource.points_per_kill;
+    }
+}
+
+/// Loads the [`HighScores`] from [`HIGH_SCORES_PATH`], and saves each run that makes the
+/// table as it ends.
+pub struct HighScorePlugin;
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HighScores::load(HIGH_SCORES_PATH))
+            .add_system(Self::record_run.in_schedule(OnEnter(GameState::GameOver)));
+    }
+}
+
+impl HighScorePlugin {
+    fn record_run(
+        run_stats: Res<RunStats>,
+        waves: Res<Waves>,
+        mut high_scores: ResMut<HighScores>,
+    ) {
+        if high_scores.record(run_stats.score, waves.wave).is_none() {
+            return;
+        }
+
+        if let Err(err) = high_scores.save(HIGH_SCORES_PATH) {
+            warn!("Couldn't save high scores to {HIGH_SCORES_PATH}: {err}");
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::{
+    audio::AudioSettings,
+    camera_effects::CameraEffectsSettings,
+    display::DisplaySettings,
+    menu::{
+        MenuAction, MenuAdjustEvent, MenuBackEvent, MenuBuilder, MenuButtonLabel, MenuConfirmEvent,
+        MenuStack, MenuSystem, BACK_KEYS,
+    },
+    player::{Slot, SlotBindings},
+};
+
+/// What a button on the settings menu changes.
+#[derive(Component, Clone, Copy, Debug)]
+enum Setting {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    DisplayMode,
+    ScreenEffects,
+    ScreenShake,
+    DamageFlashes,
+    Binding(Slot),
+    Done,
+}
+
+impl Setting {
+    /// Ability3 and Ability4 have nothing in them to bind
+    const ALL: [Setting; 12] = [
+        Setting::MasterVolume,
+        Setting::MusicVolume,
+        Setting::SfxVolume,
+        Setting::DisplayMode,
+        Setting::ScreenEffects,
+        Setting::ScreenShake,
+        Setting::DamageFlashes,
+        Setting::Binding(Slot::Primary),
+        Setting::Binding(Slot::Secondary),
+        Setting::Binding(Slot::Ability1),
+        Setting::Binding(Slot::Ability2),
+        Setting::Done,
+    ];
+}
+
+#[derive(Component)]
+struct SettingsMenu;
+
+/// The slot waiting for a key press to bind to it.
+#[derive(Resource, Default)]
+struct PendingBinding(Option<Slot>);
+
+/// Opens the settings menu on top of whatever is open, returning it.
+pub fn open_settings_menu(
+    commands: &mut Commands,
+    menu_stack: &mut MenuStack,
+    font: Handle<Font>,
+) -> Entity {
+    // The labels are filled in with the current values once spawned
+    let menu = Setting::ALL
+        .into_iter()
+        .fold(MenuBuilder::new("Settings"), |menu, setting| {
+            menu.with_button("", setting)
+        })
+        .spawn(commands, menu_stack, font);
+
+    commands.entity(menu).insert(SettingsMenu);
+    menu
+}
+
+/// Steps a volume by tenths, without drifting off them.
+fn step_volume(volume: &mut f32, step: i32) {
+    *volume = ((*volume * 10.).round() + step as f32).clamp(0., 10.) / 10.;
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "On"
+    } else {
+        "Off"
+    }
+}
+
+/// Volumes, display mode, accessibility and key bindings, changed from a menu and applied
+/// as soon as they change.
+pub struct SettingsMenuPlugin;
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingBinding>()
+            .add_system(Self::capture_binding.before(MenuSystem))
+            .add_systems(
+                (
+                    Self::change_settings,
+                    Self::close_settings,
+                    Self::label_settings.after(Self::change_settings),
+                )
+                    .after(MenuSystem),
+            );
+    }
+}
+
+impl SettingsMenuPlugin {
+    /// Binds the next key pressed to the pending slot, or cancels on a back key. Keys that
+    /// move the player are passed over, waiting on another.
+    fn capture_binding(
+        keyboard: Res<Input<KeyCode>>,
+        mut pending_binding: ResMut<PendingBinding>,
+        mut slot_bindings: ResMut<SlotBindings>,
+        mut action_query: Query<&mut ActionState<MenuAction>>,
+    ) {
+        let Some(slot) = pending_binding.0 else {
+            return;
+        };
+        let Some(&key) = keyboard.get_just_pressed().next() else {
+            return;
+        };
+
+        if BACK_KEYS.contains(&key) || slot_bindings.bind(slot, key) {
+            pending_binding.0 = None;
+        }
+
+        // The key shouldn't also move through the menu
+        for mut action_state in action_query.iter_mut() {
+            for action in MenuAction::variants() {
+                action_state.consume(action);
+            }
+        }
+    }
+
+    fn change_settings(
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        mut ev_adjust: EventReader<MenuAdjustEvent>,
+        setting_query: Query<&Setting>,
+        mut audio_settings: ResMut<AudioSettings>,
+        mut display_settings: ResMut<DisplaySettings>,
+        mut camera_effects_settings: ResMut<CameraEffectsSettings>,
+        mut pending_binding: ResMut<PendingBinding>,
+    ) {
+        // Confirming steps forward, except on bindings where it waits for a key
+        let changes = ev_confirm
+            .iter()
+            .map(|ev| (ev.0, 0))
+            .chain(ev_adjust.iter().map(|ev| (ev.button, ev.step)));
+
+        for (button, step) in changes {
+            let Ok(&setting) = setting_query.get(button) else {
+                continue;
+            };
+            pending_binding.0 = None;
+            let forward = step >= 0;
+            let step = if step == 0 { 1 } else { step };
+
+            match setting {
+                Setting::MasterVolume => step_volume(&mut audio_settings.master_volume, step),
+                Setting::MusicVolume => step_volume(&mut audio_settings.music_volume, step),
+                Setting::SfxVolume => step_volume(&mut audio_settings.sfx_volume, step),
+                Setting::DisplayMode => {
+                    display_settings.mode = if forward {
+                        display_settings.mode.next()
+                    } else {
+                        display_settings.mode.previous()
+                    };
+                }
+                Setting::ScreenEffects => {
+                    camera_effects_settings.enabled = !camera_effects_settings.enabled;
+                }
+                Setting::ScreenShake => {
+                    let intensity = camera_effects_settings.intensity + step as f32 * 0.25;
+                    camera_effects_settings.intensity = intensity.clamp(0., 1.);
+                }
+                Setting::DamageFlashes => {
+                    camera_effects_settings.flash = !camera_effects_settings.flash;
+                }
+                Setting::Binding(slot) => pending_binding.0 = Some(slot),
+                Setting::Done => {}
+            }
+        }
+    }
+
+    fn close_settings(
+        mut commands: Commands,
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        mut ev_back: EventReader<MenuBackEvent>,
+        setting_query: Query<(&Setting, &Parent)>,
+        menu_query: Query<(), With<SettingsMenu>>,
+        mut pending_binding: ResMut<PendingBinding>,
+    ) {
+        let done = ev_confirm
+            .iter()
+            .filter_map(|ev| match setting_query.get(ev.0) {
+                Ok((Setting::Done, parent)) => Some(parent.get()),
+                _ => None,
+            });
+        let backed_out = ev_back.iter().map(|ev| ev.0);
+
+        for menu in done.chain(backed_out) {
+            if menu_query.contains(menu) {
+                commands.entity(menu).despawn_recursive();
+                pending_binding.0 = None;
+            }
+        }
+    }
+
+    fn label_settings(
+        setting_query: Query<(&Setting, &Children)>,
+        mut label_query: Query<&mut Text, With<MenuButtonLabel>>,
+        audio_settings: Res<AudioSettings>,
+        display_settings: Res<DisplaySettings>,
+        camera_effects_settings: Res<CameraEffectsSettings>,
+        slot_bindings: Res<SlotBindings>,
+        pending_binding: Res<PendingBinding>,
+    ) {
+        let percent = |fraction: f32| format!("{:.0}%", fraction * 100.);
+
+        for (&setting, children) in setting_query.iter() {
+            let label = match setting {
+                Setting::MasterVolume => {
+                    format!("Master volume: {}", percent(audio_settings.master_volume))
+                }
+                Setting::MusicVolume => {
+                    format!("Music volume: {}", percent(audio_settings.music_volume))
+                }
+                Setting::SfxVolume => {
+                    format!("Effects volume: {}", percent(audio_settings.sfx_volume))
+                }
+                Setting::DisplayMode => format!("Display: {:?}", display_settings.mode),
+                Setting::ScreenEffects => format!(
+                    "Screen effects: {}",
+                    on_off(camera_effects_settings.enabled)
+                ),
+                Setting::ScreenShake => format!(
+                    "Screen shake: {}",
+                    percent(camera_effects_settings.intensity)
+                ),
+                Setting::DamageFlashes => {
+                    format!("Damage flashes: {}", on_off(camera_effects_settings.flash))
+                }
+                Setting::Binding(slot) if pending_binding.0 == Some(slot) => {
+                    format!("{slot:?}: press a key")
+                }
+                Setting::Binding(slot) => match slot_bindings.key(slot) {
+                    Some(key) => format!("{slot:?}: {key:?}"),
+                    None => format!("{slot:?}: -"),
+                },
+                Setting::Done => "Done".to_owned(),
+            };
+
+            let mut labels = label_query.iter_many_mut(children);
+            while let Some(mut text) = labels.fetch_next() {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.clone();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+/// Every one-shot sound effect in the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    ShootBullet,
+    ShootBomb,
+    ShootMissile,
+    BulletHit,
+    BombHit,
+    Evade,
+    Thruster,
+    PowerUp,
+}
+
+impl Sfx {
+    pub const ALL: [Sfx; 8] = [
+        Sfx::ShootBullet,
+        Sfx::ShootBomb,
+        Sfx::ShootMissile,
+        Sfx::BulletHit,
+        Sfx::BombHit,
+        Sfx::Evade,
+        Sfx::Thruster,
+        Sfx::PowerUp,
+    ];
+
+    /// The path shared by every variant, before the `_000.ogg` suffix
+    fn stem(&self) -> &'static str {
+        match self {
+            Sfx::ShootBullet => "Audio/laserSmall",
+            Sfx::ShootBomb => "Audio/laserLarge",
+            Sfx::ShootMissile => "Audio/laserRetro",
+            Sfx::BulletHit => "Audio/explosionCrunch",
+            Sfx::BombHit => "Audio/lowFrequency_explosion",
+            Sfx::Evade => "Audio/forceField",
+            Sfx::Thruster => "Audio/thrusterFire",
+            Sfx::PowerUp => "Audio/computerNoise",
+        }
+    }
+
+    /// How many numbered variants of the sound there are to pick from at random
+    pub fn variant_count(&self) -> usize {
+        match self {
+            Sfx::BombHit => 2,
+            Sfx::PowerUp => 4,
+            _ => 5,
+        }
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = String> {
+        let stem = self.stem();
+        (0..self.variant_count()).map(move |variant| format!("{stem}_{variant:03}.ogg"))
+    }
+
+    /// How many copies of the sound can play at once, so rapid fire doesn't stack up
+    pub fn max_voices(&self) -> usize {
+        match self {
+            Sfx::ShootBullet | Sfx::BulletHit => 4,
+            Sfx::ShootMissile | Sfx::BombHit | Sfx::Thruster | Sfx::PowerUp => 2,
+            Sfx::ShootBomb | Sfx::Evade => 1,
+        }
+    }
+
+    /// How long the sound plays for in seconds, which is how long a voice stays taken.
+    ///
+    /// Sounds are cut off after this long, which turns long recordings into short bursts.
+    pub fn length(&self) -> f32 {
+        match self {
+            Sfx::ShootBullet => 0.41,
+            Sfx::ShootBomb => 0.74,
+            Sfx::ShootMissile => 0.28,
+            Sfx::BulletHit => 1.98,
+            Sfx::BombHit => 2.,
+            Sfx::Evade => 0.96,
+            // The recordings are five second loops
+            Sfx::Thruster => 0.35,
+            Sfx::PowerUp => 5.,
+        }
+    }
+}
+
+/// Requests a one-shot sound effect.
+///
+/// Gameplay systems send this instead of using [`bevy::audio::Audio`] directly, so they
+/// keep working when no audio output is present.
+pub struct PlaySfxEvent(pub Sfx);
@@ -0,0 +1,214 @@
+//! Headless simulation of the game, for tests and tooling.
+//!
+//! A [`Simulation`] runs [`GameplayPlugin`] under `MinimalPlugins` with a fixed timestep,
+//! so scenarios play out identically on every run and never need a window, a GPU or an
+//! audio device.
+
+use std::{
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    ecs::query::ReadOnlyWorldQuery, input::InputPlugin, prelude::*, time::TimeUpdateStrategy,
+    utils::HashSet,
+};
+use leafwing_input_manager::{plugin::InputManagerSystem, prelude::*};
+
+use crate::{
+    player::{Movement, Player, Slot},
+    pool::Pooled,
+    GameState, GameplayPlugin,
+};
+
+/// Actions held down by the test driving a [`Simulation`], in place of real input.
+#[derive(Resource)]
+struct ScriptedActions<A: Actionlike> {
+    /// By [`Actionlike::index`]
+    held: HashSet<usize>,
+    _phantom: PhantomData<A>,
+}
+
+impl<A: Actionlike> Default for ScriptedActions<A> {
+    fn default() -> Self {
+        Self {
+            held: HashSet::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+pub struct Simulation {
+    pub app: App,
+    /// How much game time passes on every tick
+    pub timestep: Duration,
+    now: Instant,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulation {
+    /// Builds the headless app and runs the startup systems, so the player and the
+    /// initial enemy already exist when this returns.
+    pub fn new() -> Self {
+        Self::build(|_| {})
+    }
+
+    /// Like [`Simulation::new`], also adding `plugin` before anything runs, for testing
+    /// plugins outside [`GameplayPlugin`] that don't need a window or an audio device.
+    pub fn with_plugin(plugin: impl Plugin) -> Self {
+        Self::build(|app| {
+            app.add_plugin(plugin);
+        })
+    }
+
+    fn build(add_plugins: impl FnOnce(&mut App)) -> Self {
+        let mut app = App::new();
+
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(InputPlugin)
+            .add_plugin(GameplayPlugin)
+            .init_resource::<ScriptedActions<Movement>>()
+            .init_resource::<ScriptedActions<Slot>>()
+            .add_system(
+                Self::apply_scripted_actions::<Movement>
+                    .in_base_set(CoreSet::PreUpdate)
+                    .in_set(InputManagerSystem::ManualControl),
+            )
+            .add_system(
+                Self::apply_scripted_actions::<Slot>
+                    .in_base_set(CoreSet::PreUpdate)
+                    .in_set(InputManagerSystem::ManualControl),
+            )
+            .add_system(Self::detach_input_maps.in_base_set(CoreSet::PostUpdate));
+        add_plugins(&mut app);
+
+        // Nothing can be loaded without the renderer and audio plugins, so skip straight
+        // to gameplay
+        app.insert_resource(NextState(Some(GameState::Playing)));
+
+        let now = Instant::now();
+        app.insert_resource(TimeUpdateStrategy::ManualInstant(now));
+        app.update();
+
+        Self {
+            app,
+            timestep: Duration::from_secs_f64(1. / 60.),
+            now,
+        }
+    }
+
+    /// Advances the simulation by `ticks` fixed timesteps.
+    pub fn step(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.now += self.timestep;
+            self.app
+                .insert_resource(TimeUpdateStrategy::ManualInstant(self.now));
+            self.app.update();
+        }
+    }
+
+    /// Starts holding `action` down on the player until [`Simulation::release`] is called.
+    pub fn hold<A: Actionlike>(&mut self, action: A) {
+        self.scripted_actions::<A>().held.insert(action.index());
+    }
+
+    pub fn release<A: Actionlike>(&mut self, action: A) {
+        self.scripted_actions::<A>().held.remove(&action.index());
+    }
+
+    /// Presses `action` for a single tick, then lets it go on the next one.
+    pub fn tap<A: Actionlike>(&mut self, action: A) {
+        self.hold(action.clone());
+        self.step(1);
+        self.release(action);
+        self.step(1);
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world.resource::<R>()
+    }
+
+    /// Counts the entities matching the query filter `F`, e.g. `count::<With<Enemy>>()`.
+    pub fn count<F: ReadOnlyWorldQuery>(&mut self) -> usize {
+        self.app
+            .world
+            .query_filtered::<Entity, F>()
+            .iter(&self.app.world)
+            .count()
+    }
+
+    /// Like [`Simulation::count`], but leaves out entities parked in a pool.
+    pub fn count_active<F: ReadOnlyWorldQuery>(&mut self) -> usize {
+        self.app
+            .world
+            .query_filtered::<Option<&Pooled>, F>()
+            .iter(&self.app.world)
+            .filter(|pooled| pooled.is_none_or(|pooled| pooled.is_active()))
+            .count()
+    }
+
+    /// Despawns every entity matching the query filter `F`, to clear the stage for a scenario.
+    pub fn despawn_all<F: ReadOnlyWorldQuery>(&mut self) {
+        let entities: Vec<Entity> = self
+            .app
+            .world
+            .query_filtered::<Entity, F>()
+            .iter(&self.app.world)
+            .collect();
+
+        for entity in entities {
+            despawn_with_children_recursive(&mut self.app.world, entity);
+        }
+    }
+
+    pub fn spawn(&mut self, bundle: impl Bundle) -> Entity {
+        self.app.world.spawn(bundle).id()
+    }
+
+    pub fn player_transform(&mut self) -> Transform {
+        *self
+            .app
+            .world
+            .query_filtered::<&Transform, With<Player>>()
+            .single(&self.app.world)
+    }
+
+    fn scripted_actions<A: Actionlike>(&mut self) -> Mut<'_, ScriptedActions<A>> {
+        self.app.world.resource_mut::<ScriptedActions<A>>()
+    }
+
+    fn apply_scripted_actions<A: Actionlike>(
+        scripted_actions: Res<ScriptedActions<A>>,
+        mut action_query: Query<&mut ActionState<A>, With<Player>>,
+    ) {
+        for mut action_state in action_query.iter_mut() {
+            for action in A::variants() {
+                if scripted_actions.held.contains(&action.index()) {
+                    action_state.press(action);
+                } else {
+                    action_state.release(action);
+                }
+            }
+        }
+    }
+
+    /// The player's input maps would overwrite the scripted actions every frame, so they
+    /// are removed as soon as the player spawns.
+    fn detach_input_maps(mut commands: Commands, player_query: Query<Entity, Added<Player>>) {
+        for player_entity in player_query.iter() {
+            commands
+                .entity(player_entity)
+                .remove::<(InputMap<Movement>, InputMap<Slot>)>();
+        }
+    }
+}
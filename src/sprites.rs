@@ -0,0 +1,178 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::assets::GameAssets;
+
+/// One of the packed sprite sheets in `assets/Tilemap`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpriteSheet {
+    Ships,
+    Tiles,
+}
+
+impl SpriteSheet {
+    pub const ALL: [SpriteSheet; 2] = [SpriteSheet::Ships, SpriteSheet::Tiles];
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            SpriteSheet::Ships => "Tilemap/ships_packed.png",
+            SpriteSheet::Tiles => "Tilemap/tiles_packed.png",
+        }
+    }
+
+    pub fn tile_size(&self) -> Vec2 {
+        match self {
+            SpriteSheet::Ships => Vec2::splat(32.),
+            SpriteSheet::Tiles => Vec2::splat(16.),
+        }
+    }
+
+    /// The number of (columns, rows) in the sheet's grid
+    pub fn grid(&self) -> (usize, usize) {
+        match self {
+            SpriteSheet::Ships => (4, 6),
+            SpriteSheet::Tiles => (12, 10),
+        }
+    }
+
+    pub fn sprite_count(&self) -> usize {
+        let (columns, rows) = self.grid();
+        columns * rows
+    }
+}
+
+/// Every sprite the game draws, by name.
+///
+/// Indices match the numbering of the loose files in `assets/Ships` and `assets/Tiles`,
+/// e.g. [`SpriteName::PlayerShip`] is `Ships/ship_0004.png`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpriteName {
+    PlayerShip,
+    PlayerShipEvading,
+    EnemyShip,
+    Bullet,
+    Bomb,
+    BombSpin1,
+    BombSpin2,
+    Missile,
+    PowerUpPierce,
+    PowerUpRicochet,
+    PowerUpChain,
+    ExplosionSparks,
+    ExplosionSmoke,
+    ExplosionPuff,
+}
+
+impl SpriteName {
+    pub const ALL: [SpriteName; 14] = [
+        SpriteName::PlayerShip,
+        SpriteName::PlayerShipEvading,
+        SpriteName::EnemyShip,
+        SpriteName::Bullet,
+        SpriteName::Bomb,
+        SpriteName::BombSpin1,
+        SpriteName::BombSpin2,
+        SpriteName::Missile,
+        SpriteName::PowerUpPierce,
+        SpriteName::PowerUpRicochet,
+        SpriteName::PowerUpChain,
+        SpriteName::ExplosionSparks,
+        SpriteName::ExplosionSmoke,
+        SpriteName::ExplosionPuff,
+    ];
+
+    pub fn sheet(&self) -> SpriteSheet {
+        match self {
+            SpriteName::PlayerShip | SpriteName::PlayerShipEvading | SpriteName::EnemyShip => {
+                SpriteSheet::Ships
+            }
+            SpriteName::Bullet
+            | SpriteName::Bomb
+            | SpriteName::BombSpin1
+            | SpriteName::BombSpin2
+            | SpriteName::Missile
+            | SpriteName::PowerUpPierce
+            | SpriteName::PowerUpRicochet
+            | SpriteName::PowerUpChain
+            | SpriteName::ExplosionSparks
+            | SpriteName::ExplosionSmoke
+            | SpriteName::ExplosionPuff => SpriteSheet::Tiles,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        match self {
+            SpriteName::PlayerShip => 4,
+            SpriteName::PlayerShipEvading => 16,
+            SpriteName::EnemyShip => 9,
+            SpriteName::Bullet => 2,
+            SpriteName::Bomb => 4,
+            SpriteName::BombSpin1 => 5,
+            SpriteName::BombSpin2 => 6,
+            SpriteName::Missile => 0,
+            SpriteName::PowerUpPierce => 24,
+            SpriteName::PowerUpRicochet => 25,
+            SpriteName::PowerUpChain => 26,
+            SpriteName::ExplosionSparks => 7,
+            SpriteName::ExplosionSmoke => 8,
+            SpriteName::ExplosionPuff => 9,
+        }
+    }
+}
+
+/// The texture atlas for each [`SpriteSheet`], so entities can draw from a shared texture.
+#[derive(Resource)]
+pub struct SpriteRegistry {
+    atlases: HashMap<SpriteSheet, Handle<TextureAtlas>>,
+}
+
+impl FromWorld for SpriteRegistry {
+    fn from_world(world: &mut World) -> Self {
+        let textures: Vec<(SpriteSheet, Handle<Image>)> = {
+            let game_assets = world.resource::<GameAssets>();
+            SpriteSheet::ALL
+                .iter()
+                .map(|&sheet| (sheet, game_assets.sprite_sheet(sheet)))
+                .collect()
+        };
+
+        let mut texture_atlases = world.resource_mut::<Assets<TextureAtlas>>();
+        let atlases = textures
+            .into_iter()
+            .map(|(sheet, texture)| {
+                let (columns, rows) = sheet.grid();
+                let atlas =
+                    TextureAtlas::from_grid(texture, sheet.tile_size(), columns, rows, None, None);
+                (sheet, texture_atlases.add(atlas))
+            })
+            .collect();
+
+        Self { atlases }
+    }
+}
+
+impl SpriteRegistry {
+    pub fn atlas(&self, sheet: SpriteSheet) -> Handle<TextureAtlas> {
+        self.atlases[&sheet].clone()
+    }
+
+    pub fn sprite(&self, name: SpriteName) -> TextureAtlasSprite {
+        TextureAtlasSprite::new(name.index())
+    }
+
+    pub fn sprite_sheet_bundle(&self, name: SpriteName, transform: Transform) -> SpriteSheetBundle {
+        SpriteSheetBundle {
+            sprite: self.sprite(name),
+            texture_atlas: self.atlas(name.sheet()),
+            transform,
+            ..default()
+        }
+    }
+}
+
+pub struct SpriteRegistryPlugin;
+impl Plugin for SpriteRegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<TextureAtlas>()
+            .init_resource::<SpriteRegistry>();
+    }
+}
@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+
+use crate::{enemy::Waves, play_area::PlayArea, GameState};
+
+/// One depth of stars, all scrolling at the same speed.
+#[derive(Clone, Debug)]
+pub struct StarLayer {
+    pub count: usize,
+    /// The distance the stars fall per second at normal speed
+    pub speed: f32,
+    pub size: f32,
+    pub color: Color,
+}
+
+#[derive(Resource)]
+pub struct StarfieldResource {
+    /// From the farthest layer to the nearest, which is drawn on top
+    pub layers: Vec<StarLayer>,
+    /// Set while warping between waves, speeding the stars up to `warp_speed`
+    pub warp: bool,
+    /// How many times faster than normal the stars scroll while warping
+    pub warp_speed: f32,
+    /// How many times faster than normal the stars scroll while loading and on the title
+    /// and game-over screens
+    pub loading_speed: f32,
+    /// How quickly the scroll speed eases towards the one for the current state, in
+    /// multiples of normal speed per second
+    pub acceleration: f32,
+}
+
+impl Default for StarfieldResource {
+    fn default() -> Self {
+        Self {
+            layers: vec![
+                StarLayer {
+                    count: 80,
+                    speed: 15.,
+                    size: 1.,
+                    color: Color::rgb(0.4, 0.4, 0.5),
+                },
+                StarLayer {
+                    count: 40,
+                    speed: 40.,
+                    size: 2.,
+                    color: Color::rgb(0.7, 0.7, 0.8),
+                },
+                StarLayer {
+                    count: 15,
+                    speed: 90.,
+                    size: 3.,
+                    color: Color::WHITE,
+                },
+            ],
+            warp: false,
+            warp_speed: 8.,
+            loading_speed: 0.25,
+            acceleration: 4.,
+        }
+    }
+}
+
+#[derive(Component)]
+struct Star {
+    layer: usize,
+}
+
+/// How many times faster than normal the stars are scrolling right now.
+#[derive(Resource, Default)]
+pub struct ScrollSpeed(pub f32);
+
+/// Scrolls layers of stars behind the game at different speeds, to give a sense of depth
+/// and motion.
+pub struct StarfieldPlugin;
+impl Plugin for StarfieldPlugin {
+    fn build(&self, app: &mut App) {
+        // Deep space behind the stars instead of the default gray
+        app.insert_resource(ClearColor(Color::rgb(0.02, 0.02, 0.05)))
+            .init_resource::<StarfieldResource>()
+            .init_resource::<ScrollSpeed>()
+            .add_startup_system(Self::spawn_stars)
+            .add_system(Self::warp_between_waves.before(Self::scroll_stars))
+            .add_system(Self::scroll_stars);
+    }
+}
+
+impl StarfieldPlugin {
+    fn spawn_stars(
+        mut commands: Commands,
+        starfield_resource: Res<StarfieldResource>,
+        play_area: Res<PlayArea>,
+    ) {
+        for (layer, star_layer) in starfield_resource.layers.iter().enumerate() {
+            for _ in 0..star_layer.count {
+                let position = Vec2::new(
+                    (fastrand::f32() - 0.5) * play_area.width,
+                    (fastrand::f32() - 0.5) * play_area.height,
+                );
+
+                commands.spawn((
+                    Star { layer },
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: star_layer.color,
+                            custom_size: Some(Vec2::splat(star_layer.size)),
+                            ..default()
+                        },
+                        // Behind everything else, nearer layers in front of farther ones
+                        transform: Transform::from_translation(
+                            position.extend(-100. + layer as f32),
+                        ),
+                        ..default()
+                    },
+                ));
+            }
+        }
+    }
+
+    fn warp_between_waves(waves: Res<Waves>, mut starfield_resource: ResMut<StarfieldResource>) {
+        let warp = waves.is_warping();
+        if starfield_resource.warp != warp {
+            starfield_resource.warp = warp;
+        }
+    }
+
+    fn scroll_stars(
+        mut star_query: Query<(&Star, &mut Transform, &mut Sprite)>,
+        starfield_resource: Res<StarfieldResource>,
+        mut scroll_speed: ResMut<ScrollSpeed>,
+        state: Res<State<GameState>>,
+        play_area: Res<PlayArea>,
+        time: Res<Time>,
+    ) {
+        let target_speed = match state.0 {
+            GameState::Loading | GameState::MainMenu | GameState::GameOver => {
+                starfield_resource.loading_speed
+            }
+            GameState::Playing if starfield_resource.warp => starfield_resource.warp_speed,
+            GameState::Playing => 1.,
+        };
+        let max_change = starfield_resource.acceleration * time.delta_seconds();
+        scroll_speed.0 += (target_speed - scroll_speed.0).clamp(-max_change, max_change);
+
+        for (star, mut transform, mut sprite) in star_query.iter_mut() {
+            let star_layer = &starfield_resource.layers[star.layer];
+
+            transform.translation.y -= star_layer.speed * scroll_speed.0 * time.delta_seconds();
+
+            // Back in at the top once it falls off the bottom, somewhere else along it
+            if transform.translation.y < -play_area.half_height() {
+                transform.translation.y += play_area.height;
+                transform.translation.x = (fastrand::f32() - 0.5) * play_area.width;
+            }
+
+            // Stretched into streaks at speed
+            sprite.custom_size = Some(Vec2::new(
+                star_layer.size,
+                star_layer.size * scroll_speed.0.max(1.),
+            ));
+        }
+    }
+}
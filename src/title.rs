@@ -0,0 +1,204 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    assets::GameAssets,
+    despawn_all,
+    difficulty::DifficultySettings,
+    menu::{
+        MenuAdjustEvent, MenuBackEvent, MenuBuilder, MenuButtonLabel, MenuConfirmEvent, MenuStack,
+        MenuSystem,
+    },
+    score::HighScores,
+    settings::open_settings_menu,
+    GameState,
+};
+
+#[derive(Component, Clone, Copy, Debug)]
+enum TitleButton {
+    Start,
+    Difficulty,
+    Adaptive,
+    Options,
+    HighScores,
+    Quit,
+    /// Closes the high scores
+    Back,
+}
+
+#[derive(Component)]
+struct TitleMenu;
+
+#[derive(Component)]
+struct HighScoresMenu;
+
+/// The menu shown in [`GameState::MainMenu`], where runs start.
+pub struct TitleScreenPlugin;
+impl Plugin for TitleScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(Self::spawn_title_menu.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(despawn_all::<TitleMenu>.in_schedule(OnExit(GameState::MainMenu)))
+            .add_systems(
+                (
+                    Self::press_title_buttons,
+                    Self::open_submenus,
+                    Self::close_high_scores,
+                    Self::change_difficulty,
+                    Self::label_difficulty.after(Self::change_difficulty),
+                )
+                    .after(MenuSystem)
+                    .in_set(OnUpdate(GameState::MainMenu)),
+            );
+    }
+}
+
+impl TitleScreenPlugin {
+    fn spawn_title_menu(
+        mut commands: Commands,
+        mut menu_stack: ResMut<MenuStack>,
+        game_assets: Res<GameAssets>,
+    ) {
+        let menu = MenuBuilder::new("Rustaga")
+            .with_button("Start", TitleButton::Start)
+            // The difficulty labels are filled in once spawned
+            .with_button("", TitleButton::Difficulty)
+            .with_button("", TitleButton::Adaptive)
+            .with_button("Options", TitleButton::Options)
+            .with_button("High scores", TitleButton::HighScores)
+            .with_button("Quit", TitleButton::Quit)
+            .spawn(&mut commands, &mut menu_stack, game_assets.font.clone());
+
+        commands.entity(menu).insert(TitleMenu);
+    }
+
+    fn press_title_buttons(
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        button_query: Query<&TitleButton>,
+        mut next_state: ResMut<NextState<GameState>>,
+        mut ev_exit: EventWriter<AppExit>,
+    ) {
+        for ev in ev_confirm.iter() {
+            match button_query.get(ev.0) {
+                Ok(TitleButton::Start) => next_state.set(GameState::Playing),
+                Ok(TitleButton::Quit) => ev_exit.send(AppExit),
+                _ => {}
+            }
+        }
+    }
+
+    /// Opens the settings or the high scores over the title menu, closing along with it.
+    fn open_submenus(
+        mut commands: Commands,
+        mut menu_stack: ResMut<MenuStack>,
+        game_assets: Res<GameAssets>,
+        high_scores: Res<HighScores>,
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        button_query: Query<&TitleButton>,
+        title_menu_query: Query<Entity, With<TitleMenu>>,
+    ) {
+        let Ok(title_menu) = title_menu_query.get_single() else {
+            return;
+        };
+        let font = game_assets.font.clone();
+
+        for ev in ev_confirm.iter() {
+            let submenu = match button_query.get(ev.0) {
+                Ok(TitleButton::Options) => {
+                    open_settings_menu(&mut commands, &mut menu_stack, font.clone())
+                }
+                Ok(TitleButton::HighScores) => {
+                    let lines = high_scores.entries.iter().enumerate().map(|(rank, entry)| {
+                        format!("{}. {:>8}  wave {}", rank + 1, entry.score, entry.wave)
+                    });
+                    let menu = if high_scores.entries.is_empty() {
+                        MenuBuilder::new("High scores").with_line("No runs yet")
+                    } else {
+                        lines.fold(MenuBuilder::new("High scores"), MenuBuilder::with_line)
+                    }
+                    .with_button("Back", TitleButton::Back)
+                    .spawn(&mut commands, &mut menu_stack, font.clone());
+
+                    commands.entity(menu).insert(HighScoresMenu);
+                    menu
+                }
+                _ => continue,
+            };
+
+            commands.entity(title_menu).add_child(submenu);
+        }
+    }
+
+    /// Steps through the presets on confirm or left and right, and toggles adaptive mode.
+    fn change_difficulty(
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        mut ev_adjust: EventReader<MenuAdjustEvent>,
+        button_query: Query<&TitleButton>,
+        mut difficulty_settings: ResMut<DifficultySettings>,
+    ) {
+        let changes = ev_confirm
+            .iter()
+            .map(|ev| (ev.0, 1))
+            .chain(ev_adjust.iter().map(|ev| (ev.button, ev.step)));
+
+        for (button, step) in changes {
+            match button_query.get(button) {
+                Ok(TitleButton::Difficulty) => {
+                    difficulty_settings.difficulty = if step > 0 {
+                        difficulty_settings.difficulty.next()
+                    } else {
+                        difficulty_settings.difficulty.previous()
+                    };
+                }
+                Ok(TitleButton::Adaptive) => {
+                    difficulty_settings.adaptive = !difficulty_settings.adaptive;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn label_difficulty(
+        button_query: Query<(&TitleButton, &Children)>,
+        mut label_query: Query<&mut Text, With<MenuButtonLabel>>,
+        difficulty_settings: Res<DifficultySettings>,
+    ) {
+        for (button, children) in button_query.iter() {
+            let label = match button {
+                TitleButton::Difficulty => {
+                    format!("Difficulty: {:?}", difficulty_settings.difficulty)
+                }
+                TitleButton::Adaptive if difficulty_settings.adaptive => "Adaptive: On".to_owned(),
+                TitleButton::Adaptive => "Adaptive: Off".to_owned(),
+                _ => continue,
+            };
+
+            let mut labels = label_query.iter_many_mut(children);
+            while let Some(mut text) = labels.fetch_next() {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.clone();
+                }
+            }
+        }
+    }
+
+    fn close_high_scores(
+        mut commands: Commands,
+        mut ev_confirm: EventReader<MenuConfirmEvent>,
+        mut ev_back: EventReader<MenuBackEvent>,
+        button_query: Query<(&TitleButton, &Parent)>,
+        high_scores_menu_query: Query<(), With<HighScoresMenu>>,
+    ) {
+        let done = ev_confirm
+            .iter()
+            .filter_map(|ev| match button_query.get(ev.0) {
+                Ok((TitleButton::Back, parent)) => Some(parent.get()),
+                _ => None,
+            });
+        let backed_out = ev_back.iter().map(|ev| ev.0);
+
+        for menu in done.chain(backed_out) {
+            if high_scores_menu_query.contains(menu) {
+                commands.entity(menu).despawn_recursive();
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::sprites::SpriteName;
+
+/// A gameplay state that changes how an entity looks, from lowest to highest priority.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VisualState {
+    Normal,
+    PoweredUp,
+    Damaged,
+    /// Just respawned, and can't be hit yet
+    Invulnerable,
+    Evading,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Appearance {
+    /// Must be on the same sheet as the entity's texture atlas
+    pub sprite: SpriteName,
+    pub tint: Color,
+    /// How many times per second the sprite blinks between its tint and a faded copy of
+    /// it (0 for no blinking)
+    pub blink_rate: f32,
+}
+
+impl Appearance {
+    pub fn new(sprite: SpriteName) -> Self {
+        Self {
+            sprite,
+            tint: Color::WHITE,
+            blink_rate: 0.,
+        }
+    }
+
+    pub fn with_tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn with_blink_rate(mut self, blink_rate: f32) -> Self {
+        self.blink_rate = blink_rate;
+        self
+    }
+}
+
+/// The visual states an entity is currently in, and how it looks in each of them.
+///
+/// Gameplay systems only insert and remove states; the sprite and tint of the highest
+/// priority active state are applied to the entity's [`TextureAtlasSprite`] without
+/// touching anything else on it.
+#[derive(Component, Debug)]
+pub struct VisualStates {
+    appearances: HashMap<VisualState, Appearance>,
+    /// Active states, with a timer for those that wear off by themselves
+    active: HashMap<VisualState, Option<Timer>>,
+    shown: Option<VisualState>,
+    shown_for: f32,
+}
+
+impl VisualStates {
+    pub fn new(normal: Appearance) -> Self {
+        Self {
+            appearances: HashMap::from([(VisualState::Normal, normal)]),
+            active: HashMap::default(),
+            shown: None,
+            shown_for: 0.,
+        }
+    }
+
+    pub fn with(mut self, state: VisualState, appearance: Appearance) -> Self {
+        self.appearances.insert(state, appearance);
+        self
+    }
+
+    /// Enters `state` until it is removed.
+    pub fn insert(&mut self, state: VisualState) {
+        self.active.insert(state, None);
+    }
+
+    /// Enters `state` for `seconds`, restarting the countdown if already in it.
+    pub fn insert_for(&mut self, state: VisualState, seconds: f32) {
+        self.active
+            .insert(state, Some(Timer::from_seconds(seconds, TimerMode::Once)));
+    }
+
+    pub fn remove(&mut self, state: VisualState) {
+        self.active.remove(&state);
+    }
+
+    pub fn contains(&self, state: VisualState) -> bool {
+        self.active.contains_key(&state)
+    }
+
+    /// The highest priority active state that has an appearance.
+    pub fn current(&self) -> VisualState {
+        self.active
+            .keys()
+            .copied()
+            .filter(|state| self.appearances.contains_key(state))
+            .max()
+            .unwrap_or(VisualState::Normal)
+    }
+
+    pub fn appearance(&self) -> Appearance {
+        self.appearances[&self.current()]
+    }
+}
+
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VisualStateSystem;
+
+pub struct VisualStatePlugin;
+impl Plugin for VisualStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (Self::tick_visual_states, Self::apply_visual_states)
+                .chain()
+                .in_set(VisualStateSystem)
+                .in_base_set(CoreSet::PostUpdate),
+        );
+    }
+}
+
+impl VisualStatePlugin {
+    fn tick_visual_states(mut query: Query<&mut VisualStates>, time: Res<Time>) {
+        for mut visual_states in query.iter_mut() {
+            let visual_states = visual_states.bypass_change_detection();
+
+            visual_states.active.retain(|_, timer| {
+                timer
+                    .as_mut()
+                    .is_none_or(|timer| !timer.tick(time.delta()).finished())
+            });
+            visual_states.shown_for += time.delta_seconds();
+        }
+    }
+
+    fn apply_visual_states(mut query: Query<(&mut VisualStates, &mut TextureAtlasSprite)>) {
+        for (mut visual_states, mut sprite) in query.iter_mut() {
+            let current = visual_states.current();
+
+            if visual_states.shown != Some(current) {
+                visual_states.shown = Some(current);
+                visual_states.shown_for = 0.;
+            }
+
+            let appearance = visual_states.appearance();
+            let mut color = appearance.tint;
+
+            if appearance.blink_rate > 0. {
+                let blinks = (visual_states.shown_for * appearance.blink_rate) as u32;
+                if blinks.is_multiple_of(2) {
+                    color.set_a(color.a() * 0.3);
+                }
+            }
+
+            if sprite.index != appearance.sprite.index() {
+                sprite.index = appearance.sprite.index();
+            }
+            if sprite.color != color {
+                sprite.color = color;
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use rustaga::{
+    animation::{Animation, Explosion, SpriteAnimation},
+    enemy::{Enemy, EnemyDestroyedEvent},
+    player::Movement,
+    pool::{EntityPool, Pooled},
+    projectile::{FireProjectileEvent, ProjectileKind, ProjectileModifiers, ProjectileOwner},
+    sim::Simulation,
+};
+
+/// Counts the animations playing, leaving out those parked in a pool.
+fn count_animations(sim: &mut Simulation, animation: Animation) -> usize {
+    let mut animation_query = sim.world().query::<(&SpriteAnimation, Option<&Pooled>)>();
+    animation_query
+        .iter(sim.world())
+        .filter(|(sprite_animation, pooled)| {
+            sprite_animation.animation == animation && pooled.is_none_or(Pooled::is_active)
+        })
+        .count()
+}
+
+#[test]
+fn explosions_play_once_and_disappear() {
+    let mut sim = Simulation::new();
+
+    sim.world()
+        .send_event(EnemyDestroyedEvent(Vec2::new(50., 50.)));
+    sim.step(1);
+    assert_eq!(count_animations(&mut sim, Animation::Explosion), 1);
+
+    sim.step(60);
+    assert_eq!(count_animations(&mut sim, Animation::Explosion), 0);
+}
+
+#[test]
+fn explosions_are_parked_once_played_and_reused() {
+    let mut sim = Simulation::new();
+
+    sim.world()
+        .send_event(EnemyDestroyedEvent(Vec2::new(50., 50.)));
+    sim.step(60);
+    assert_eq!(sim.resource::<EntityPool<Animation>>().len(), 1);
+
+    sim.world()
+        .send_event(EnemyDestroyedEvent(Vec2::new(-50., 50.)));
+    sim.step(1);
+    assert_eq!(count_animations(&mut sim, Animation::Explosion), 1);
+    assert_eq!(sim.count::<With<Explosion>>(), 1);
+    assert!(sim.resource::<EntityPool<Animation>>().is_empty());
+}
+
+#[test]
+fn bombs_spin_through_their_frames() {
+    let mut sim = Simulation::new();
+    sim.despawn_all::<With<Enemy>>();
+
+    sim.world().send_event(FireProjectileEvent {
+        kind: ProjectileKind::Bomb,
+        owner: ProjectileOwner::Player,
+        origin: Transform::default(),
+        modifiers: ProjectileModifiers::default(),
+    });
+
+    let mut seen = Vec::new();
+    for _ in 0..30 {
+        sim.step(1);
+        let mut sprite_query = sim
+            .world()
+            .query_filtered::<&TextureAtlasSprite, With<SpriteAnimation>>();
+        for sprite in sprite_query.iter(sim.world()) {
+            if !seen.contains(&sprite.index) {
+                seen.push(sprite.index);
+            }
+        }
+    }
+
+    assert_eq!(seen.len(), Animation::BombSpin.frames().len());
+}
+
+#[test]
+fn the_player_banks_into_turns_and_levels_out() {
+    let mut sim = Simulation::new();
+
+    sim.hold(Movement::Right);
+    sim.step(30);
+    assert!(sim.player_transform().scale.x < 1.);
+
+    sim.release(Movement::Right);
+    sim.step(30);
+    assert!((sim.player_transform().scale.x - 1.).abs() < 1e-5);
+}
@@ -0,0 +1,14 @@
+use std::path::Path;
+
+use rustaga::assets::GameAssets;
+
+#[test]
+fn every_loaded_path_exists() {
+    let assets_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+
+    let missing: Vec<String> = GameAssets::paths()
+        .filter(|path| !assets_dir.join(path).is_file())
+        .collect();
+
+    assert!(missing.is_empty(), "missing assets: {missing:?}");
+}
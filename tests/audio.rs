@@ -0,0 +1,51 @@
+use rustaga::{
+    audio::{AudioChannel, AudioSettings, SfxVoices},
+    sfx::Sfx,
+};
+
+#[test]
+fn sound_effects_stop_stacking_at_their_voice_limit() {
+    let mut sfx_voices = SfxVoices::default();
+    let sfx = Sfx::ShootBullet;
+
+    let started = (0..sfx.max_voices() + 3)
+        .filter(|_| sfx_voices.try_start(sfx, 0.))
+        .count();
+    assert_eq!(started, sfx.max_voices());
+
+    // Other sounds have voices of their own
+    assert!(sfx_voices.try_start(Sfx::Evade, 0.));
+
+    // Voices free up once the sound has finished playing
+    assert!(sfx_voices.try_start(sfx, sfx.length()));
+}
+
+#[test]
+fn master_volume_scales_every_channel() {
+    let audio_settings = AudioSettings {
+        master_volume: 0.5,
+        music_volume: 0.8,
+        sfx_volume: 1.,
+    };
+
+    assert_eq!(audio_settings.volume(AudioChannel::Music), 0.4);
+    assert_eq!(audio_settings.volume(AudioChannel::Sfx), 0.5);
+}
+
+#[test]
+fn settings_survive_a_save_and_load() {
+    let path = std::env::temp_dir().join(format!("rustaga-audio-{}.ron", std::process::id()));
+    let audio_settings = AudioSettings {
+        master_volume: 0.3,
+        music_volume: 0.2,
+        sfx_volume: 0.9,
+    };
+
+    audio_settings.save(&path).unwrap();
+    let loaded = AudioSettings::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, audio_settings);
+    // Falls back to the defaults when nothing was saved
+    assert_eq!(AudioSettings::load(&path), AudioSettings::default());
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+use rustaga::{boundary::BoundaryBehavior, movement::Velocity, sim::Simulation};
+
+/// Spawns an entity in the middle of the play area heading right at 600 units per second.
+fn spawn_mover(sim: &mut Simulation, boundary_behavior: BoundaryBehavior) -> Entity {
+    sim.spawn((
+        boundary_behavior,
+        Velocity(Vec2::new(600., 0.)),
+        TransformBundle::default(),
+    ))
+}
+
+fn position_and_velocity(sim: &mut Simulation, entity: Entity) -> (Vec3, Vec2) {
+    let entity = sim.world().entity(entity);
+    (
+        entity.get::<Transform>().unwrap().translation,
+        entity.get::<Velocity>().unwrap().0,
+    )
+}
+
+#[test]
+fn wrap_reappears_at_the_opposite_edge() {
+    let mut sim = Simulation::new();
+    let mover = spawn_mover(&mut sim, BoundaryBehavior::Wrap);
+
+    sim.step(30);
+
+    let (position, velocity) = position_and_velocity(&mut sim, mover);
+    assert!(position.x < 0.);
+    assert_eq!(velocity, Vec2::new(600., 0.));
+}
+
+#[test]
+fn clamp_stops_at_the_edge() {
+    let mut sim = Simulation::new();
+    let mover = spawn_mover(&mut sim, BoundaryBehavior::Clamp);
+
+    sim.step(30);
+
+    let (position, velocity) = position_and_velocity(&mut sim, mover);
+    assert_eq!(position.x, 250.);
+    assert_eq!(velocity, Vec2::ZERO);
+}
+
+#[test]
+fn bounce_turns_around_at_the_edge() {
+    let mut sim = Simulation::new();
+    let mover = spawn_mover(&mut sim, BoundaryBehavior::Bounce);
+
+    sim.step(30);
+
+    let (position, velocity) = position_and_velocity(&mut sim, mover);
+    assert!(position.x < 250.);
+    assert_eq!(velocity, Vec2::new(-600., 0.));
+}
+
+#[test]
+fn despawn_removes_the_entity() {
+    let mut sim = Simulation::new();
+    let mover = spawn_mover(&mut sim, BoundaryBehavior::Despawn);
+
+    sim.step(30);
+
+    assert!(sim.world().get_entity(mover).is_none());
+}
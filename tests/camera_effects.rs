@@ -0,0 +1,67 @@
+mod common;
+
+use bevy::prelude::*;
+use common::with_enemy_at_using;
+use rustaga::{
+    camera_effects::{CameraEffectsPlugin, CameraEffectsSettings, CameraShakeEvent},
+    display::MainCamera,
+    enemy::Enemy,
+    player::Slot,
+    sim::Simulation,
+};
+
+/// Fires at the enemy until it's shot down, failing if it takes more than a second.
+fn shoot_down_enemy(sim: &mut Simulation) {
+    sim.tap(Slot::Primary);
+    for _ in 0..60 {
+        if sim.count::<With<Enemy>>() == 0 {
+            return;
+        }
+        sim.step(1);
+    }
+    panic!("The enemy wasn't shot down");
+}
+
+#[test]
+fn kills_briefly_slow_down_time() {
+    let mut sim = with_enemy_at_using(
+        Simulation::with_plugin(CameraEffectsPlugin),
+        Vec2::new(0., -100.),
+    );
+
+    shoot_down_enemy(&mut sim);
+    sim.step(1);
+    assert!(sim.resource::<Time>().relative_speed() < 1.);
+
+    sim.step(30);
+    assert_eq!(sim.resource::<Time>().relative_speed(), 1.);
+}
+
+#[test]
+fn disabled_effects_leave_time_alone() {
+    let mut sim = with_enemy_at_using(
+        Simulation::with_plugin(CameraEffectsPlugin),
+        Vec2::new(0., -100.),
+    );
+    sim.world().resource_mut::<CameraEffectsSettings>().enabled = false;
+
+    shoot_down_enemy(&mut sim);
+    sim.step(1);
+
+    assert_eq!(sim.resource::<Time>().relative_speed(), 1.);
+}
+
+#[test]
+fn camera_shake_settles_back_where_it_started() {
+    let mut sim = Simulation::with_plugin(CameraEffectsPlugin);
+    let camera = sim.spawn((MainCamera, TransformBundle::default()));
+    let camera_translation =
+        |sim: &mut Simulation| sim.world().get::<Transform>(camera).unwrap().translation;
+
+    sim.world().send_event(CameraShakeEvent(1.));
+    sim.step(1);
+    assert_ne!(camera_translation(&mut sim), Vec3::ZERO);
+
+    sim.step(120);
+    assert!(camera_translation(&mut sim).length() < 0.001);
+}
@@ -0,0 +1,31 @@
+// Each test crate uses only some of these
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use rustaga::{
+    enemy::{Enemy, EnemyResource, Health},
+    sim::Simulation,
+};
+
+/// A simulation with a single stationary enemy at `position` and nothing else on stage.
+pub fn with_enemy_at(position: Vec2) -> Simulation {
+    with_enemy_at_using(Simulation::new(), position)
+}
+
+/// Like [`with_enemy_at`], clearing the stage of `sim` instead, such as one built with
+/// [`Simulation::with_plugin`].
+pub fn with_enemy_at_using(mut sim: Simulation, position: Vec2) -> Simulation {
+    sim.world().resource_mut::<EnemyResource>().speed = 0.;
+    sim.despawn_all::<With<Enemy>>();
+    spawn_enemy_at(&mut sim, position);
+    sim
+}
+
+pub fn spawn_enemy_at(sim: &mut Simulation, position: Vec2) -> Entity {
+    let health = sim.resource::<EnemyResource>().health;
+    sim.spawn((
+        Enemy,
+        Health(health),
+        TransformBundle::from_transform(Transform::from_translation(position.extend(0.))),
+    ))
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use rustaga::{
+    difficulty::{AdaptiveDifficulty, Difficulty, DifficultyResource, DifficultySettings},
+    enemy::{Enemy, EnemyResource, Health, Waves},
+    movement::Velocity,
+    player::PlayerResource,
+    pool::Pooled,
+    projectile::{Projectile, ProjectileKind},
+    sim::Simulation,
+    GameState,
+};
+
+/// Starts a fresh run with `difficulty_settings`.
+fn restart_with(sim: &mut Simulation, difficulty_settings: DifficultySettings) {
+    sim.world().insert_resource(difficulty_settings);
+    sim.world()
+        .insert_resource(NextState(Some(GameState::Playing)));
+    sim.step(1);
+}
+
+fn enemy_shots(sim: &mut Simulation) -> usize {
+    let world = sim.world();
+    world
+        .query::<(&Projectile, &Pooled)>()
+        .iter(world)
+        .filter(|(projectile, pooled)| {
+            projectile.kind == ProjectileKind::EnemyShot && pooled.is_active()
+        })
+        .count()
+}
+
+#[test]
+fn presets_scale_enemies_and_the_starting_kit() {
+    let mut sim = Simulation::new();
+    let enemy_resource = sim.resource::<EnemyResource>();
+    let (speed, health) = (enemy_resource.speed, enemy_resource.health);
+    let PlayerResource { bombs, evades, .. } = PlayerResource::default();
+    let scaling = Difficulty::Hard.scaling();
+
+    restart_with(
+        &mut sim,
+        DifficultySettings {
+            difficulty: Difficulty::Hard,
+            adaptive: false,
+        },
+    );
+
+    let world = sim.world();
+    let (enemy_health, velocity) = world
+        .query_filtered::<(&Health, &Velocity), With<Enemy>>()
+        .single(world);
+    assert_eq!(enemy_health.0, health * scaling.enemy_health);
+    assert_eq!(velocity.x, -speed * scaling.enemy_speed);
+
+    let player_resource = sim.resource::<PlayerResource>();
+    assert_eq!(player_resource.bombs, bombs - 1);
+    assert_eq!(player_resource.evades, evades - 1);
+}
+
+#[test]
+fn enemies_hold_their_fire_longer_on_easy() {
+    let mut sim = Simulation::new();
+    let fire_rate = sim.resource::<EnemyResource>().fire_rate;
+    let first_shot = (60. / fire_rate) as u32;
+
+    sim.step(first_shot);
+    assert_eq!(enemy_shots(&mut sim), 1);
+
+    restart_with(
+        &mut sim,
+        DifficultySettings {
+            difficulty: Difficulty::Easy,
+            adaptive: false,
+        },
+    );
+    sim.step(first_shot);
+    assert_eq!(enemy_shots(&mut sim), 0);
+}
+
+#[test]
+fn adaptive_mode_follows_how_the_player_is_doing() {
+    let mut sim = Simulation::new();
+    let wave_delay = sim.resource::<EnemyResource>().wave_delay;
+    let intensity_step = sim.resource::<DifficultyResource>().intensity_step;
+    restart_with(
+        &mut sim,
+        DifficultySettings {
+            difficulty: Difficulty::Normal,
+            adaptive: true,
+        },
+    );
+
+    // Clearing a wave without losing a life brings more enemies
+    sim.despawn_all::<With<Enemy>>();
+    sim.step(2);
+    assert_eq!(
+        sim.resource::<AdaptiveDifficulty>().intensity,
+        1. + intensity_step
+    );
+    sim.step((wave_delay * 60.) as u32);
+    assert_eq!(sim.resource::<Waves>().wave, 2);
+    assert_eq!(sim.count::<With<Enemy>>(), 2);
+
+    // And losing one eases off again
+    sim.world().resource_mut::<PlayerResource>().health = 0.;
+    sim.step(2);
+    assert_eq!(sim.resource::<AdaptiveDifficulty>().intensity, 1.);
+}
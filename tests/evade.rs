@@ -0,0 +1,65 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{spawn_enemy_at, with_enemy_at};
+use rustaga::{
+    enemy::Enemy,
+    evade::{EvadeResource, EvadeTimer},
+    player::{Movement, PlayerResource, Slot, PLAYER_START},
+    sim::Simulation,
+};
+
+#[test]
+fn touching_an_enemy_hurts() {
+    let mut sim = with_enemy_at(PLAYER_START);
+    let health = sim.resource::<PlayerResource>().health;
+
+    sim.step(1);
+
+    assert!(sim.resource::<PlayerResource>().health < health);
+    assert_eq!(sim.count::<With<Enemy>>(), 0);
+}
+
+#[test]
+fn evading_player_cannot_be_hit() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    let health = sim.resource::<PlayerResource>().health;
+
+    sim.tap(Slot::Ability1);
+    assert_eq!(sim.count::<With<EvadeTimer>>(), 1);
+
+    spawn_enemy_at(&mut sim, PLAYER_START);
+    sim.step(10);
+
+    assert_eq!(sim.resource::<PlayerResource>().health, health);
+}
+
+#[test]
+fn evading_with_a_direction_dashes() {
+    let mut walking = Simulation::new();
+    walking.hold(Movement::Right);
+    walking.step(20);
+
+    let mut dashing = Simulation::new();
+    dashing.hold(Movement::Right);
+    dashing.tap(Slot::Ability1);
+    dashing.step(18);
+
+    assert!(
+        dashing.player_transform().translation.x > walking.player_transform().translation.x + 50.
+    );
+}
+
+#[test]
+fn evades_recharge_up_to_the_maximum() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    let max_evades = sim.resource::<EvadeResource>().max_evades;
+
+    sim.tap(Slot::Ability1);
+    assert_eq!(sim.resource::<PlayerResource>().evades, max_evades - 1);
+
+    let recharge_ticks = (sim.resource::<EvadeResource>().recharge_time * 60.) as u32;
+    sim.step(recharge_ticks * 3);
+
+    assert_eq!(sim.resource::<PlayerResource>().evades, max_evades);
+}
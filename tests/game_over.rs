@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use rustaga::{
+    enemy::Enemy,
+    game_over::GameOverScreenPlugin,
+    lives::{Lives, LivesResource},
+    menu::{Menu, MenuButton, MenuConfirmEvent, MenuPlugin},
+    player::{Player, PlayerResource},
+    score::RunStats,
+    sim::Simulation,
+    GameState,
+};
+
+/// The game-over screen and the menus it's built on.
+struct GameOverMenuPlugin;
+impl Plugin for GameOverMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(MenuPlugin).add_plugin(GameOverScreenPlugin);
+    }
+}
+
+/// Loses the last life and lets the continue run out straight away.
+fn game_over(sim: &mut Simulation) {
+    sim.world().resource_mut::<LivesResource>().continue_time = 0.;
+    sim.world().resource_mut::<Lives>().remaining = 0;
+    sim.world().resource_mut::<RunStats>().score = 300;
+    sim.world().resource_mut::<PlayerResource>().health = 0.;
+    sim.step(3);
+}
+
+#[test]
+fn running_out_of_lives_ends_the_run() {
+    let mut sim = Simulation::with_plugin(GameOverMenuPlugin);
+
+    game_over(&mut sim);
+
+    assert_eq!(sim.resource::<State<GameState>>().0, GameState::GameOver);
+    assert_eq!(sim.count::<With<Player>>(), 0);
+    assert_eq!(sim.count::<With<Enemy>>(), 0);
+    // Kept for the game-over screen
+    assert_eq!(sim.resource::<RunStats>().score, 300);
+    assert_eq!(sim.count::<With<Menu>>(), 1);
+}
+
+#[test]
+fn retrying_starts_a_fresh_run() {
+    let mut sim = Simulation::with_plugin(GameOverMenuPlugin);
+    game_over(&mut sim);
+
+    // Retry is the first button
+    let retry = {
+        let world = sim.world();
+        world
+            .query::<(Entity, &MenuButton)>()
+            .iter(world)
+            .find(|(_, button)| button.index == 0)
+            .map(|(entity, _)| entity)
+            .unwrap()
+    };
+    sim.world().send_event(MenuConfirmEvent(retry));
+    sim.step(2);
+
+    let player_resource = sim.resource::<PlayerResource>();
+    assert_eq!(player_resource.health, player_resource.max_health);
+    assert_eq!(sim.resource::<State<GameState>>().0, GameState::Playing);
+    assert_eq!(sim.resource::<RunStats>().score, 0);
+    assert_eq!(sim.count::<With<Player>>(), 1);
+    assert_eq!(sim.count::<With<Menu>>(), 0);
+}
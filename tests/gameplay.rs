@@ -0,0 +1,120 @@
+mod common;
+
+use bevy::prelude::*;
+use common::with_enemy_at;
+use rustaga::{
+    enemy::{Enemy, EnemyResource, Waves},
+    play_area::PlayArea,
+    player::{Movement, PlayerResource, Slot, PLAYER_START},
+    pool::Pooled,
+    projectile::{Projectile, ProjectileKind},
+    sim::Simulation,
+};
+
+fn count_projectiles(sim: &mut Simulation, kind: ProjectileKind) -> usize {
+    let world = sim.world();
+    world
+        .query::<(&Projectile, &Pooled)>()
+        .iter(world)
+        .filter(|(projectile, pooled)| projectile.kind == kind && pooled.is_active())
+        .count()
+}
+
+#[test]
+fn startup_spawns_player_and_enemy() {
+    let mut sim = Simulation::new();
+
+    assert_eq!(sim.player_transform().translation, PLAYER_START.extend(0.));
+    assert_eq!(sim.count::<With<Enemy>>(), 1);
+}
+
+#[test]
+fn holding_left_moves_player_left() {
+    let mut sim = Simulation::new();
+
+    sim.hold(Movement::Left);
+    sim.step(30);
+
+    let expected = -sim.resource::<PlayerResource>().movement_speed * 0.5;
+    assert!((sim.player_transform().translation.x - expected).abs() < 1.);
+}
+
+#[test]
+fn shooting_spawns_a_bullet_that_travels_up() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+
+    sim.tap(Slot::Primary);
+    assert_eq!(count_projectiles(&mut sim, ProjectileKind::Bullet), 1);
+
+    let mut bullet_query = sim.world().query_filtered::<&Transform, With<Projectile>>();
+    let start_y = bullet_query.single(sim.world()).translation.y;
+    sim.step(10);
+    assert!(bullet_query.single(sim.world()).translation.y > start_y);
+}
+
+#[test]
+fn bullet_destroys_enemy_on_contact() {
+    let mut sim = with_enemy_at(Vec2::new(0., 0.));
+
+    sim.tap(Slot::Primary);
+    sim.step(60);
+
+    assert_eq!(sim.count::<With<Enemy>>(), 0);
+    assert_eq!(count_projectiles(&mut sim, ProjectileKind::Bullet), 0);
+}
+
+#[test]
+fn clearing_a_wave_warps_to_a_bigger_one() {
+    let mut sim = Simulation::new();
+    let wave_delay = sim.resource::<EnemyResource>().wave_delay;
+    assert_eq!(sim.resource::<Waves>().wave, 1);
+
+    sim.despawn_all::<With<Enemy>>();
+    sim.step(1);
+    assert!(sim.resource::<Waves>().is_warping());
+
+    sim.step((wave_delay * 60.) as u32 + 1);
+    assert!(!sim.resource::<Waves>().is_warping());
+    assert_eq!(sim.resource::<Waves>().wave, 2);
+    assert_eq!(sim.count::<With<Enemy>>(), 2);
+}
+
+#[test]
+fn bombs_run_out() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    let bombs = sim.resource::<PlayerResource>().bombs;
+
+    for _ in 0..bombs + 2 {
+        sim.tap(Slot::Secondary);
+    }
+
+    assert_eq!(sim.resource::<PlayerResource>().bombs, 0);
+    assert_eq!(
+        count_projectiles(&mut sim, ProjectileKind::Bomb),
+        bombs as usize
+    );
+}
+
+#[test]
+fn bullets_despawn_past_the_top_of_the_play_area() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+
+    sim.tap(Slot::Primary);
+    sim.step(120);
+
+    assert_eq!(count_projectiles(&mut sim, ProjectileKind::Bullet), 0);
+}
+
+#[test]
+fn player_wraps_around_the_play_area() {
+    let mut sim = Simulation::new();
+    sim.world().insert_resource(PlayArea {
+        width: 100.,
+        height: 500.,
+    });
+
+    sim.hold(Movement::Right);
+    sim.step(13);
+
+    assert!(sim.player_transform().translation.x < 0.);
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use rustaga::{
+    lives::{ContinueEvent, Invulnerable, Lives, LivesResource, Respawn},
+    player::{Player, PlayerResource, PLAYER_START},
+    projectile::{FireProjectileEvent, ProjectileKind, ProjectileModifiers, ProjectileOwner},
+    score::RunStats,
+    sim::Simulation,
+    GameState,
+};
+
+fn die(sim: &mut Simulation) {
+    sim.world().resource_mut::<PlayerResource>().health = 0.;
+    sim.step(1);
+}
+
+fn player_has<C: Component>(sim: &mut Simulation) -> bool {
+    sim.count::<(With<Player>, With<C>)>() == 1
+}
+
+#[test]
+fn losing_a_life_flies_the_player_back_in() {
+    let mut sim = Simulation::new();
+    let lives = sim.resource::<Lives>().remaining;
+    let respawn_delay = sim.resource::<LivesResource>().respawn_delay;
+
+    die(&mut sim);
+    assert_eq!(sim.resource::<Lives>().remaining, lives - 1);
+    assert!(player_has::<Respawn>(&mut sim));
+    assert!(sim.player_transform().translation.y < PLAYER_START.y);
+
+    sim.step((respawn_delay * 60.) as u32 + 60);
+    assert!(!player_has::<Respawn>(&mut sim));
+    assert_eq!(sim.player_transform().translation, PLAYER_START.extend(0.));
+    let player_resource = sim.resource::<PlayerResource>();
+    assert_eq!(player_resource.health, player_resource.max_health);
+    assert_eq!(sim.resource::<State<GameState>>().0, GameState::Playing);
+}
+
+#[test]
+fn respawned_players_cant_be_hit_for_a_while() {
+    let mut sim = Simulation::new();
+    let lives_resource = sim.resource::<LivesResource>();
+    let respawn_time = lives_resource.respawn_delay + 1.;
+    let invulnerability = lives_resource.invulnerability;
+
+    die(&mut sim);
+    sim.step((respawn_time * 60.) as u32);
+    assert!(player_has::<Invulnerable>(&mut sim));
+
+    let health = sim.resource::<PlayerResource>().health;
+    let origin = sim.player_transform() * Transform::from_xyz(0., -40., 0.);
+    sim.world().send_event(FireProjectileEvent {
+        kind: ProjectileKind::Bullet,
+        owner: ProjectileOwner::Enemy,
+        origin,
+        modifiers: ProjectileModifiers::default(),
+    });
+    sim.step(30);
+    assert_eq!(sim.resource::<PlayerResource>().health, health);
+
+    sim.step((invulnerability * 60.) as u32);
+    assert!(!player_has::<Invulnerable>(&mut sim));
+}
+
+#[test]
+fn scoring_past_a_threshold_awards_an_extra_life() {
+    let mut sim = Simulation::new();
+    let lives = sim.resource::<Lives>().remaining;
+    let extra_life_every = sim.resource::<LivesResource>().extra_life_every;
+
+    sim.world().resource_mut::<RunStats>().score = extra_life_every;
+    sim.step(2);
+    assert_eq!(sim.resource::<Lives>().remaining, lives + 1);
+
+    // Only once per threshold
+    sim.world().resource_mut::<RunStats>().score = extra_life_every * 2 - 1;
+    sim.step(2);
+    assert_eq!(sim.resource::<Lives>().remaining, lives + 1);
+}
+
+#[test]
+fn the_last_life_offers_a_continue() {
+    let mut sim = Simulation::new();
+    let starting_lives = sim.resource::<LivesResource>().starting_lives;
+    sim.world().resource_mut::<Lives>().remaining = 0;
+
+    die(&mut sim);
+    assert!(sim.resource::<Lives>().is_offering_continue());
+    assert_eq!(sim.resource::<State<GameState>>().0, GameState::Playing);
+
+    sim.world().send_event(ContinueEvent);
+    sim.step(1);
+    let lives = sim.resource::<Lives>();
+    assert!(!lives.is_offering_continue());
+    assert_eq!(lives.remaining, starting_lives);
+    assert_eq!(lives.continues_used, 1);
+}
+
+#[test]
+fn letting_the_continue_run_out_ends_the_run() {
+    let mut sim = Simulation::new();
+    let continue_time = sim.resource::<LivesResource>().continue_time;
+    sim.world().resource_mut::<Lives>().remaining = 0;
+
+    die(&mut sim);
+    sim.step((continue_time * 60.) as u32 + 2);
+
+    assert_eq!(sim.resource::<State<GameState>>().0, GameState::GameOver);
+}
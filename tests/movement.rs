@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use rustaga::{
+    player::{Movement, MovementModel, Player, PlayerResource, Thrust},
+    sim::Simulation,
+};
+
+fn with_player_resource(configure: impl FnOnce(&mut PlayerResource)) -> Simulation {
+    let mut sim = Simulation::new();
+    configure(&mut sim.world().resource_mut::<PlayerResource>());
+    sim
+}
+
+#[test]
+fn opposite_directions_cancel_out() {
+    let mut sim = Simulation::new();
+
+    sim.hold(Movement::Left);
+    sim.hold(Movement::Right);
+    sim.step(30);
+
+    assert_eq!(sim.player_transform().translation.x, 0.);
+}
+
+#[test]
+fn vertical_input_is_ignored_without_a_band() {
+    let mut sim = Simulation::new();
+    let start_y = sim.player_transform().translation.y;
+
+    sim.hold(Movement::Up);
+    sim.step(30);
+
+    assert_eq!(sim.player_transform().translation.y, start_y);
+}
+
+#[test]
+fn vertical_movement_stays_within_the_band() {
+    let mut sim = with_player_resource(|player| player.vertical_band = Some(0.3));
+
+    sim.hold(Movement::Up);
+    sim.step(120);
+
+    // The default play area is 500 tall, so the band tops out 150 above its bottom edge
+    let y = sim.player_transform().translation.y;
+    assert!((y - -100.).abs() < 0.01, "{y}");
+}
+
+#[test]
+fn inertial_movement_accelerates_and_coasts() {
+    let mut sim = with_player_resource(|player| {
+        player.movement_model = MovementModel::Inertial {
+            acceleration: 500.,
+            friction: 500.,
+        }
+    });
+
+    sim.hold(Movement::Right);
+    sim.step(6);
+    let x_after_first_steps = sim.player_transform().translation.x;
+    sim.step(6);
+    let x_after_more_steps = sim.player_transform().translation.x;
+
+    // Still speeding up, so the second stretch covers more ground than the first
+    assert!(x_after_more_steps - x_after_first_steps > x_after_first_steps);
+
+    sim.release(Movement::Right);
+    sim.step(1);
+    let x_on_release = sim.player_transform().translation.x;
+    sim.step(60);
+    let x_at_rest = sim.player_transform().translation.x;
+
+    assert!(x_at_rest > x_on_release);
+    sim.step(10);
+    assert_eq!(sim.player_transform().translation.x, x_at_rest);
+}
+
+#[test]
+fn thrust_follows_movement_input() {
+    let mut sim = with_player_resource(|player| {
+        player.movement_model = MovementModel::Inertial {
+            acceleration: 500.,
+            friction: 500.,
+        }
+    });
+    let thrust = |sim: &mut Simulation| {
+        let mut thrust_query = sim.world().query_filtered::<&Thrust, With<Player>>();
+        let thrust = thrust_query.single(sim.world());
+        (thrust.direction, thrust.throttle)
+    };
+
+    sim.hold(Movement::Left);
+    sim.step(6);
+    let (direction, throttle) = thrust(&mut sim);
+    assert_eq!(direction, Vec2::NEG_X);
+    assert!(throttle > 0. && throttle < 1., "{throttle}");
+
+    sim.step(60);
+    assert_eq!(thrust(&mut sim).1, 1.);
+
+    sim.release(Movement::Left);
+    sim.step(60);
+    assert_eq!(thrust(&mut sim), (Vec2::ZERO, 0.));
+}
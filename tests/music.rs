@@ -0,0 +1,60 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{spawn_enemy_at, with_enemy_at};
+use rustaga::{
+    music::{MusicCue, MusicResource, MusicTrack},
+    sim::Simulation,
+};
+
+#[test]
+fn gameplay_music_intensifies_with_the_number_of_enemies() {
+    let mut sim = with_enemy_at(Vec2::new(0., 200.));
+    sim.step(1);
+
+    let enemies_for_full_intensity = sim.resource::<MusicResource>().enemies_for_full_intensity;
+    let music_cue = sim.resource::<MusicCue>();
+    assert_eq!(music_cue.track, MusicTrack::Gameplay);
+    assert_eq!(music_cue.intensity, 1. / enemies_for_full_intensity as f32);
+
+    for i in 0..enemies_for_full_intensity * 2 {
+        spawn_enemy_at(&mut sim, Vec2::new(i as f32 * 10. - 200., 150.));
+    }
+    sim.step(1);
+
+    assert_eq!(sim.resource::<MusicCue>().intensity, 1.);
+}
+
+#[test]
+fn music_without_a_layer_quickens_as_it_intensifies() {
+    let mut sim = with_enemy_at(Vec2::new(0., 200.));
+    sim.step(1);
+
+    let music_cue = sim.resource::<MusicCue>();
+    assert_eq!(MusicTrack::Gameplay.layer_path(), None);
+
+    let music_resource = sim.resource::<MusicResource>();
+    assert_eq!(music_resource.speed(0.), 1.);
+    assert!(music_resource.speed(music_cue.intensity) > 1.);
+    assert_eq!(music_resource.speed(1.), 1. + music_resource.max_speedup);
+}
+
+#[test]
+fn boss_fights_switch_to_the_boss_track() {
+    let mut sim = Simulation::new();
+    let boss_phases = sim.resource::<MusicResource>().boss_phases;
+
+    sim.world().resource_mut::<MusicCue>().boss_phase = Some(0);
+    sim.step(1);
+    let music_cue = sim.resource::<MusicCue>();
+    assert_eq!(music_cue.track, MusicTrack::Boss);
+    assert_eq!(music_cue.intensity, 1. / boss_phases as f32);
+
+    sim.world().resource_mut::<MusicCue>().boss_phase = Some(boss_phases - 1);
+    sim.step(1);
+    assert_eq!(sim.resource::<MusicCue>().intensity, 1.);
+
+    sim.world().resource_mut::<MusicCue>().boss_phase = None;
+    sim.step(1);
+    assert_eq!(sim.resource::<MusicCue>().track, MusicTrack::Gameplay);
+}
@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use rustaga::{
+    enemy::EnemyDestroyedEvent,
+    particles::{Particle, ParticleKind, ParticleResource, SpawnParticlesEvent},
+    pool::{EntityPool, Pooled},
+    sim::Simulation,
+};
+
+fn count_particles(sim: &mut Simulation, kind: ParticleKind) -> usize {
+    let mut particle_query = sim.world().query::<(&Particle, &Pooled)>();
+    particle_query
+        .iter(sim.world())
+        .filter(|(particle, pooled)| particle.kind == kind && pooled.is_active())
+        .count()
+}
+
+#[test]
+fn destroyed_enemies_burst_into_an_explosion() {
+    let mut sim = Simulation::new();
+    let burst = sim
+        .resource::<ParticleResource>()
+        .effect(ParticleKind::Explosion)
+        .burst;
+
+    sim.world()
+        .send_event(EnemyDestroyedEvent(Vec2::new(50., 50.)));
+    sim.step(1);
+
+    assert_eq!(count_particles(&mut sim, ParticleKind::Explosion), burst);
+}
+
+#[test]
+fn the_player_leaves_an_engine_trail() {
+    let mut sim = Simulation::new();
+
+    sim.step(30);
+
+    assert!(count_particles(&mut sim, ParticleKind::EngineTrail) > 0);
+}
+
+#[test]
+fn live_particles_never_exceed_the_cap() {
+    let mut sim = Simulation::new();
+    sim.world().resource_mut::<ParticleResource>().max_particles = 30;
+
+    for _ in 0..5 {
+        sim.world().send_event(SpawnParticlesEvent {
+            kind: ParticleKind::BombBlast,
+            position: Vec2::ZERO,
+        });
+    }
+    sim.step(1);
+
+    assert_eq!(sim.count_active::<With<Particle>>(), 30);
+}
+
+#[test]
+fn particles_are_parked_at_the_end_of_their_life() {
+    let mut sim = Simulation::new();
+    let burst = sim
+        .resource::<ParticleResource>()
+        .effect(ParticleKind::Explosion)
+        .burst;
+
+    sim.world().send_event(SpawnParticlesEvent {
+        kind: ParticleKind::Explosion,
+        position: Vec2::ZERO,
+    });
+    sim.step(60);
+
+    assert_eq!(count_particles(&mut sim, ParticleKind::Explosion), 0);
+    assert!(sim.resource::<EntityPool<ParticleKind>>().len() >= burst);
+}
@@ -0,0 +1,146 @@
+use bevy::{
+    input::{keyboard::KeyboardInput, ButtonState},
+    prelude::*,
+};
+use rustaga::{
+    audio::AudioSettings,
+    camera_effects::CameraEffectsSettings,
+    display::DisplaySettings,
+    enemy::Enemy,
+    menu::{Menu, MenuPlugin},
+    pause::{PauseMenuPlugin, PauseState},
+    player::{PlayerResource, Slot, SlotBindings, PLAYER_START},
+    projectile::Projectile,
+    settings::SettingsMenuPlugin,
+    sim::Simulation,
+    GameState,
+};
+
+/// The pause and settings menus, with the settings they change but no window or audio.
+struct MenusPlugin;
+impl Plugin for MenusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AudioSettings>()
+            .init_resource::<DisplaySettings>()
+            .init_resource::<CameraEffectsSettings>()
+            .add_plugin(MenuPlugin)
+            .add_plugin(PauseMenuPlugin)
+            .add_plugin(SettingsMenuPlugin);
+    }
+}
+
+fn enemy_position(sim: &mut Simulation) -> Vec3 {
+    let world = sim.world();
+    world
+        .query_filtered::<&Transform, With<Enemy>>()
+        .single(world)
+        .translation
+}
+
+fn press_key(sim: &mut Simulation, key_code: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        sim.world().send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state,
+        });
+        sim.step(1);
+    }
+}
+
+#[test]
+fn pausing_freezes_gameplay_and_time() {
+    let mut sim = Simulation::new();
+    sim.world()
+        .insert_resource(NextState(Some(PauseState::Paused)));
+    sim.step(1);
+
+    let position = enemy_position(&mut sim);
+    let elapsed = sim.resource::<Time>().elapsed();
+    sim.step(30);
+
+    assert_eq!(enemy_position(&mut sim), position);
+    assert_eq!(sim.resource::<Time>().elapsed(), elapsed);
+
+    sim.world()
+        .insert_resource(NextState(Some(PauseState::Running)));
+    sim.step(30);
+
+    assert_ne!(enemy_position(&mut sim), position);
+    assert!(sim.resource::<Time>().elapsed() > elapsed);
+}
+
+#[test]
+fn restarting_starts_a_fresh_run() {
+    let mut sim = Simulation::new();
+    let bombs = sim.resource::<PlayerResource>().bombs;
+    let max_health = sim.resource::<PlayerResource>().max_health;
+
+    sim.tap(Slot::Secondary);
+    assert_eq!(sim.resource::<PlayerResource>().bombs, bombs - 1);
+    sim.world().resource_mut::<PlayerResource>().health = max_health / 2.;
+
+    sim.world()
+        .insert_resource(NextState(Some(GameState::Playing)));
+    sim.step(1);
+
+    assert_eq!(sim.resource::<PlayerResource>().bombs, bombs);
+    assert_eq!(sim.resource::<PlayerResource>().health, max_health);
+    assert_eq!(sim.count_active::<With<Projectile>>(), 0);
+    assert_eq!(sim.count::<With<Enemy>>(), 1);
+    assert_eq!(sim.player_transform().translation, PLAYER_START.extend(0.));
+}
+
+#[test]
+fn slots_cant_be_bound_to_movement_or_pause_keys() {
+    let mut slot_bindings = SlotBindings::default();
+
+    for key in [KeyCode::A, KeyCode::Left, KeyCode::Escape] {
+        assert!(!slot_bindings.bind(Slot::Primary, key));
+    }
+    assert_eq!(slot_bindings.0, SlotBindings::default().0);
+
+    assert!(slot_bindings.bind(Slot::Primary, KeyCode::C));
+    assert_eq!(slot_bindings.key(Slot::Primary), Some(KeyCode::C));
+}
+
+#[test]
+fn escape_opens_the_pause_menu_and_closes_it_again() {
+    let mut sim = Simulation::with_plugin(MenusPlugin);
+
+    press_key(&mut sim, KeyCode::Escape);
+    assert_eq!(sim.resource::<State<PauseState>>().0, PauseState::Paused);
+    assert_eq!(sim.count::<With<Menu>>(), 1);
+
+    press_key(&mut sim, KeyCode::Escape);
+    assert_eq!(sim.resource::<State<PauseState>>().0, PauseState::Running);
+    assert_eq!(sim.count::<With<Menu>>(), 0);
+}
+
+#[test]
+fn settings_open_over_the_pause_menu_and_back_out_to_it() {
+    let mut sim = Simulation::with_plugin(MenusPlugin);
+
+    press_key(&mut sim, KeyCode::Escape);
+    press_key(&mut sim, KeyCode::Down);
+    press_key(&mut sim, KeyCode::Return);
+    assert_eq!(sim.count::<With<Menu>>(), 2);
+
+    press_key(&mut sim, KeyCode::Escape);
+    assert_eq!(sim.count::<With<Menu>>(), 1);
+    assert_eq!(sim.resource::<State<PauseState>>().0, PauseState::Paused);
+}
+
+#[test]
+fn settings_apply_as_soon_as_they_change() {
+    let mut sim = Simulation::with_plugin(MenusPlugin);
+    let master_volume = sim.resource::<AudioSettings>().master_volume;
+
+    press_key(&mut sim, KeyCode::Escape);
+    press_key(&mut sim, KeyCode::Down);
+    press_key(&mut sim, KeyCode::Return);
+    // Master volume is at the top
+    press_key(&mut sim, KeyCode::Left);
+
+    assert!(sim.resource::<AudioSettings>().master_volume < master_volume);
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{spawn_enemy_at, with_enemy_at};
+use rustaga::{
+    enemy::Enemy,
+    player::{PlayerResource, Slot},
+    power_up::{PowerUp, PowerUpResource},
+};
+
+#[test]
+fn shooting_down_enough_enemies_drops_a_power_up() {
+    let mut sim = with_enemy_at(Vec2::new(0., 0.));
+    sim.world().resource_mut::<PowerUpResource>().kills_per_drop = 2;
+    spawn_enemy_at(&mut sim, Vec2::new(0., 100.));
+
+    sim.tap(Slot::Primary);
+    sim.step(60);
+    assert_eq!(sim.count::<With<Enemy>>(), 1);
+    assert_eq!(sim.count::<With<PowerUp>>(), 0);
+
+    sim.tap(Slot::Primary);
+    sim.step(80);
+    assert_eq!(sim.count::<With<Enemy>>(), 0);
+    assert_eq!(sim.count::<With<PowerUp>>(), 1);
+}
+
+#[test]
+fn collecting_a_power_up_modifies_the_players_projectiles() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    let position = sim.player_transform().translation;
+    sim.spawn((
+        PowerUp::Pierce,
+        TransformBundle::from_transform(Transform::from_translation(position)),
+    ));
+
+    sim.step(1);
+
+    assert_eq!(sim.count::<With<PowerUp>>(), 0);
+    assert_eq!(
+        sim.resource::<PlayerResource>().projectile_modifiers,
+        PowerUp::Pierce.modifiers()
+    );
+}
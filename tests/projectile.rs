@@ -0,0 +1,194 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{spawn_enemy_at, with_enemy_at};
+use rustaga::{
+    enemy::{Enemy, Health},
+    player::{PlayerResource, Slot},
+    pool::EntityPool,
+    projectile::{
+        FireProjectileEvent, Projectile, ProjectileKind, ProjectileModifiers, ProjectileOwner,
+        ProjectileResource,
+    },
+    sim::Simulation,
+};
+
+#[test]
+fn piercing_bullet_passes_through_enemies() {
+    let mut sim = with_enemy_at(Vec2::new(0., -100.));
+    spawn_enemy_at(&mut sim, Vec2::new(0., 0.));
+    sim.world()
+        .resource_mut::<ProjectileResource>()
+        .kinds
+        .get_mut(&ProjectileKind::Bullet)
+        .unwrap()
+        .modifiers
+        .pierce = 1;
+
+    sim.tap(Slot::Primary);
+    sim.step(60);
+
+    assert_eq!(sim.count::<With<Enemy>>(), 0);
+}
+
+#[test]
+fn projectiles_expire_after_their_lifetime() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    sim.world()
+        .resource_mut::<ProjectileResource>()
+        .kinds
+        .get_mut(&ProjectileKind::Bullet)
+        .unwrap()
+        .lifetime = Some(0.25);
+
+    sim.tap(Slot::Primary);
+    assert_eq!(sim.count_active::<With<Projectile>>(), 1);
+
+    sim.step(15);
+    assert_eq!(sim.count_active::<With<Projectile>>(), 0);
+}
+
+#[test]
+fn enemy_projectiles_hit_the_player_but_not_enemies() {
+    let mut sim = Simulation::new();
+    // Enough to survive the hit, which would otherwise cost a life
+    sim.world().resource_mut::<PlayerResource>().health = 1000.;
+    let health = sim.resource::<PlayerResource>().health;
+    // Fired from just below the player, so the bullet flies up into them
+    let origin = sim.player_transform() * Transform::from_xyz(0., -40., 0.);
+
+    sim.world().send_event(FireProjectileEvent {
+        kind: ProjectileKind::Bullet,
+        owner: ProjectileOwner::Enemy,
+        origin,
+        modifiers: default(),
+    });
+    sim.step(10);
+
+    assert!(sim.resource::<PlayerResource>().health < health);
+    assert_eq!(sim.count_active::<With<Projectile>>(), 0);
+    assert_eq!(sim.count::<With<Enemy>>(), 1);
+}
+
+#[test]
+fn homing_missile_steers_into_an_enemy_off_to_the_side() {
+    let mut sim = with_enemy_at(Vec2::new(150., 0.));
+
+    sim.tap(Slot::Ability2);
+    sim.step(120);
+
+    assert_eq!(sim.count::<With<Enemy>>(), 0);
+    assert_eq!(sim.count_active::<With<Projectile>>(), 0);
+}
+
+#[test]
+fn homing_missile_retargets_when_its_target_dies() {
+    let mut sim = with_enemy_at(Vec2::new(-150., 0.));
+    let near = spawn_enemy_at(&mut sim, Vec2::new(0., -100.));
+
+    sim.tap(Slot::Ability2);
+    sim.world().despawn(near);
+    sim.step(120);
+
+    assert_eq!(sim.count::<With<Enemy>>(), 0);
+}
+
+#[test]
+fn homing_missiles_run_out() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    let missiles = sim.resource::<PlayerResource>().missiles;
+
+    for _ in 0..missiles + 2 {
+        sim.tap(Slot::Ability2);
+    }
+
+    assert_eq!(sim.resource::<PlayerResource>().missiles, 0);
+    assert_eq!(sim.count_active::<With<Projectile>>(), missiles as usize);
+}
+
+#[test]
+fn chaining_bullet_arcs_to_a_nearby_enemy() {
+    let mut sim = with_enemy_at(Vec2::new(0., 0.));
+    spawn_enemy_at(&mut sim, Vec2::new(100., 0.));
+    let out_of_range = spawn_enemy_at(&mut sim, Vec2::new(-200., 100.));
+    sim.world()
+        .resource_mut::<PlayerResource>()
+        .projectile_modifiers = ProjectileModifiers {
+        chain: 2,
+        chain_range: 150.,
+        ..default()
+    };
+
+    sim.tap(Slot::Primary);
+    sim.step(60);
+
+    assert_eq!(sim.count::<With<Enemy>>(), 1);
+    assert!(sim.world().get_entity(out_of_range).is_some());
+}
+
+#[test]
+fn ricocheting_bullet_bounces_back_into_the_play_area() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    sim.world()
+        .resource_mut::<PlayerResource>()
+        .projectile_modifiers
+        .ricochets = 1;
+
+    sim.tap(Slot::Primary);
+    sim.step(120);
+
+    let mut bullet_query = sim.world().query_filtered::<&Transform, With<Projectile>>();
+    let bullet_y = bullet_query.single(sim.world()).translation.y;
+    assert!(bullet_y < 200.);
+
+    // Out of ricochets, it leaves through the bottom edge
+    sim.step(120);
+    assert_eq!(sim.count_active::<With<Projectile>>(), 0);
+}
+
+#[test]
+fn projectiles_never_damage_the_same_enemy_twice() {
+    let mut sim = with_enemy_at(Vec2::new(0., 0.));
+    let enemy = spawn_enemy_at(&mut sim, Vec2::new(0., 100.));
+    sim.world().entity_mut(enemy).insert(Health(150.));
+    sim.world()
+        .resource_mut::<PlayerResource>()
+        .projectile_modifiers = ProjectileModifiers {
+        pierce: 3,
+        ricochets: 1,
+        ..default()
+    };
+
+    // Passes through on the way up, then again after bouncing off the top edge
+    sim.tap(Slot::Primary);
+    sim.step(180);
+
+    assert_eq!(sim.world().get::<Health>(enemy).unwrap().0, 50.);
+}
+
+#[test]
+fn spent_projectiles_are_parked_and_reused() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+
+    sim.tap(Slot::Primary);
+    sim.step(120);
+    assert_eq!(sim.count_active::<With<Projectile>>(), 0);
+    assert_eq!(sim.resource::<EntityPool<ProjectileKind>>().len(), 1);
+
+    sim.tap(Slot::Primary);
+    assert_eq!(sim.count_active::<With<Projectile>>(), 1);
+    assert_eq!(sim.count::<With<Projectile>>(), 1);
+    assert!(sim.resource::<EntityPool<ProjectileKind>>().is_empty());
+}
+
+#[test]
+fn spent_projectiles_are_despawned_without_pooling() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    sim.world().resource_mut::<ProjectileResource>().pooling = false;
+
+    sim.tap(Slot::Primary);
+    sim.step(120);
+
+    assert_eq!(sim.count::<With<Projectile>>(), 0);
+    assert!(sim.resource::<EntityPool<ProjectileKind>>().is_empty());
+}
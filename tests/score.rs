@@ -0,0 +1,69 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{spawn_enemy_at, with_enemy_at};
+use rustaga::{
+    player::Slot,
+    score::{HighScore, HighScores, RunStats, ScoreResource},
+};
+
+#[test]
+fn destroying_enemies_scores_points_and_lands_shots() {
+    let mut sim = with_enemy_at(Vec2::new(0., 0.));
+    spawn_enemy_at(&mut sim, Vec2::new(200., 200.));
+    let points_per_kill = sim.resource::<ScoreResource>().points_per_kill;
+
+    // The first hits, the second flies past the enemy that's left
+    sim.tap(Slot::Primary);
+    sim.step(60);
+    sim.tap(Slot::Primary);
+    sim.step(60);
+
+    let run_stats = sim.resource::<RunStats>();
+    assert_eq!(run_stats.score, points_per_kill);
+    assert_eq!(run_stats.shots_fired, 2);
+    assert_eq!(run_stats.shots_landed, 1);
+    assert_eq!(run_stats.accuracy(), 0.5);
+}
+
+#[test]
+fn high_scores_keep_the_best_runs_in_order() {
+    let mut high_scores = HighScores::default();
+    assert_eq!(high_scores.record(0, 1), None);
+
+    for score in 1..=HighScores::MAX_ENTRIES as u32 {
+        high_scores.record(score * 100, 1);
+    }
+    assert_eq!(high_scores.record(50, 1), None);
+    assert_eq!(high_scores.record(250, 3), Some(3));
+
+    let scores: Vec<_> = high_scores
+        .entries
+        .iter()
+        .map(|entry| entry.score)
+        .collect();
+    assert_eq!(scores, [500, 400, 300, 250, 200]);
+    assert_eq!(
+        high_scores.entries[3],
+        HighScore {
+            score: 250,
+            wave: 3
+        }
+    );
+}
+
+#[test]
+fn high_scores_survive_a_save_and_load() {
+    let path = std::env::temp_dir().join(format!("rustaga-scores-{}.ron", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut high_scores = HighScores::default();
+    high_scores.record(1200, 4);
+
+    high_scores.save(&path).unwrap();
+    let loaded = HighScores::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, high_scores);
+    // Empty when nothing was saved
+    assert_eq!(HighScores::load(&path), HighScores::default());
+}
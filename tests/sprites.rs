@@ -0,0 +1,12 @@
+use rustaga::sprites::SpriteName;
+
+#[test]
+fn every_named_sprite_is_inside_its_sheet() {
+    for name in SpriteName::ALL {
+        assert!(
+            name.index() < name.sheet().sprite_count(),
+            "{name:?} is out of bounds of {:?}",
+            name.sheet()
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use rustaga::{
+    enemy::Enemy,
+    sim::Simulation,
+    starfield::{ScrollSpeed, StarfieldPlugin, StarfieldResource},
+    GameState,
+};
+
+#[test]
+fn stars_scroll_at_the_speed_for_the_state_and_warp_between_waves() {
+    let mut sim = Simulation::with_plugin(StarfieldPlugin);
+    // Straight to each target speed, instead of easing into it
+    sim.world().resource_mut::<StarfieldResource>().acceleration = 1000.;
+    let starfield_resource = sim.resource::<StarfieldResource>();
+    let (warp_speed, loading_speed) = (
+        starfield_resource.warp_speed,
+        starfield_resource.loading_speed,
+    );
+
+    sim.step(1);
+    assert!(!sim.resource::<StarfieldResource>().warp);
+    assert_eq!(sim.resource::<ScrollSpeed>().0, 1.);
+
+    sim.despawn_all::<With<Enemy>>();
+    sim.step(2);
+    assert!(sim.resource::<StarfieldResource>().warp);
+    assert_eq!(sim.resource::<ScrollSpeed>().0, warp_speed);
+
+    sim.world()
+        .insert_resource(NextState(Some(GameState::GameOver)));
+    sim.step(1);
+    assert_eq!(sim.resource::<ScrollSpeed>().0, loading_speed);
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use bevy::prelude::*;
+use common::{spawn_enemy_at, with_enemy_at};
+use rustaga::{
+    player::{Player, Slot},
+    sim::Simulation,
+    sprites::SpriteName,
+    visual_state::{VisualState, VisualStates},
+};
+
+fn player_sprite(sim: &mut Simulation) -> TextureAtlasSprite {
+    sim.world()
+        .query_filtered::<&TextureAtlasSprite, With<Player>>()
+        .single(sim.world())
+        .clone()
+}
+
+fn player_visual_state(sim: &mut Simulation) -> VisualState {
+    sim.world()
+        .query_filtered::<&VisualStates, With<Player>>()
+        .single(sim.world())
+        .current()
+}
+
+#[test]
+fn evading_swaps_the_sprite_and_back() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+
+    sim.tap(Slot::Ability1);
+    assert_eq!(player_visual_state(&mut sim), VisualState::Evading);
+    assert_eq!(
+        player_sprite(&mut sim).index,
+        SpriteName::PlayerShipEvading.index()
+    );
+
+    sim.step(120);
+    assert_eq!(player_visual_state(&mut sim), VisualState::Normal);
+    let sprite = player_sprite(&mut sim);
+    assert_eq!(sprite.index, SpriteName::PlayerShip.index());
+    assert_eq!(sprite.color, Color::WHITE);
+}
+
+#[test]
+fn evading_keeps_the_player_transform() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+    let rotation = Quat::from_rotation_z(0.3);
+    let scale = Vec3::splat(2.);
+    {
+        let world = sim.world();
+        let mut transform = world
+            .query_filtered::<&mut Transform, With<Player>>()
+            .single_mut(world);
+        transform.rotation = rotation;
+        transform.scale = scale;
+    }
+
+    sim.tap(Slot::Ability1);
+    sim.step(120);
+
+    let transform = sim.player_transform();
+    assert_eq!(transform.rotation, rotation);
+    assert_eq!(transform.scale, scale);
+}
+
+#[test]
+fn taking_damage_wears_off() {
+    let mut sim = with_enemy_at(Vec2::new(200., 200.));
+
+    spawn_enemy_at(&mut sim, Vec2::new(0., -200.));
+    sim.step(1);
+    assert_eq!(player_visual_state(&mut sim), VisualState::Damaged);
+
+    sim.step(60);
+    assert_eq!(player_visual_state(&mut sim), VisualState::Normal);
+}